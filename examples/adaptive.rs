@@ -0,0 +1,23 @@
+use tgraph::{func, Color, Graph, GraphOptions, Sampling, Theme};
+
+/// Draw narrow spikes between columns and a fast oscillation, once sampled once per column and once adaptively
+fn main() {
+    for sampling in [Sampling::Uniform, Sampling::Adaptive] {
+        println!("{:?}", sampling);
+        Graph::with_options(
+            func!(|x| {
+                let spike = 12f64 * (-((x % 20f64 - 10.4) / 0.15).powi(2)).exp();
+                spike + (x * 3.3).sin() + 2f64
+            }),
+            80,
+            None,
+            GraphOptions::builder()
+                .color(Color::Green.into())
+                .sampling(sampling)
+                .build(),
+            Theme::default(),
+        )
+        .draw();
+        println!();
+    }
+}
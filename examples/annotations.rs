@@ -0,0 +1,27 @@
+use tgraph::{func, Color, GraphOptions, MultiGraph, MultiGraphOptions};
+
+/// Mark the extrema of a wave, the root of a line and where both cross
+fn main() {
+    MultiGraph::with_options(
+        vec![
+            func!(|x| (x / 8f64).sin() * 6f64 + 9f64),
+            func!(|x| 14f64 - x / 5f64),
+        ],
+        80,
+        None,
+        MultiGraphOptions::builder()
+            .series(vec![
+                GraphOptions::builder()
+                    .color(Color::Red.into())
+                    .extrema(true)
+                    .build(),
+                GraphOptions::builder()
+                    .color(Color::Blue.into())
+                    .roots(true)
+                    .build(),
+            ])
+            .intersections(true)
+            .build(),
+    )
+    .draw();
+}
@@ -0,0 +1,20 @@
+use tgraph::{BarChart, BarChartOptions, Color};
+
+/// Draw a horizontal bar chart, labels are printed in a column before the bars
+/// and each bar ends with its value
+fn main() {
+    BarChart::with_options(
+        vec![
+            ("auth-service", 412.5),
+            ("billing", 127.0),
+            ("src/notifications/email_dispatcher.rs", 88.25),
+            ("search", 301.0),
+            ("gateway", 0.4),
+        ],
+        80,
+        BarChartOptions::builder()
+            .color(Color::Green.into())
+            .build(),
+    )
+    .draw();
+}
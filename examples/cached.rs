@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tgraph::{Function, MultiGraph};
+
+static EVALUATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Slow-changing simulation, counting how many times it runs
+fn simulation(x: f64) -> f64 {
+    EVALUATIONS.fetch_add(1, Ordering::Relaxed);
+    // Damped oscillator, integrated step by step up to x
+    let (mut position, mut velocity) = (8f64, 0f64);
+    for _ in 0..(x * 100f64) as u32 {
+        velocity += (-0.05 * position - 0.02 * velocity) * 0.01;
+        position += velocity * 0.01;
+    }
+    position + 9f64
+}
+
+/// Draw an expensive function twice, evaluating it only once per column when the graph is created
+fn main() {
+    let f = Function::new(simulation as fn(f64) -> f64);
+    let graph = MultiGraph::new(vec![f], 80, None);
+    graph.draw();
+    println!();
+    graph.draw();
+    println!();
+    println!(
+        "simulation evaluated {} times",
+        EVALUATIONS.load(Ordering::Relaxed)
+    );
+}
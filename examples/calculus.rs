@@ -0,0 +1,30 @@
+use tgraph::{func, Color, GraphOptions, Integration, MultiGraph};
+
+/// Overlay the derivative of a wave and the integral of a bump, and print the area under the bump
+fn main() {
+    let bump = func!(|x| 0.6 * (-((x - 30f64) / 8f64).powi(2)).exp());
+    println!(
+        "area under the bump: {:.4}",
+        bump.integrate(0f64, 80f64, Integration::Simpson)
+    );
+    MultiGraph::with_options(
+        vec![
+            func!(|x| (x / 5f64).sin() * 5f64 + 6f64),
+            func!(|x| 0.6 * (-((x - 30f64) / 8f64).powi(2)).exp()),
+        ],
+        80,
+        None,
+        vec![
+            GraphOptions::builder()
+                .color(Color::Red.into())
+                .derivative(true)
+                .build(),
+            GraphOptions::builder()
+                .color(Color::Blue.into())
+                .integral(true)
+                .build(),
+        ]
+        .into(),
+    )
+    .draw();
+}
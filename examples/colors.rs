@@ -0,0 +1,33 @@
+use tgraph::{ColorSupport, ColorWrapper, Function, MultiGraph, MultiGraphOptions, Theme};
+
+/// Draw series in a gradient of RGB colors, as they look in terminals with less colors
+fn main() {
+    println!("detected: {:?}", ColorSupport::detect());
+    for support in [
+        ColorSupport::TrueColor,
+        ColorSupport::Ansi256,
+        ColorSupport::Basic,
+        ColorSupport::None,
+    ] {
+        support.force();
+        println!("{:?}", support);
+        let theme = Theme::gradient(
+            ColorWrapper::rgb(30, 144, 255),
+            ColorWrapper::rgb(255, 69, 0),
+            6,
+        );
+        let functions = (0..6)
+            .map(|i| {
+                Function::new(move |x: f64| (x / 6f64 + i as f64).sin() * 3f64 + 4f64 + i as f64)
+            })
+            .collect();
+        MultiGraph::with_options(
+            functions,
+            70,
+            None,
+            MultiGraphOptions::builder().theme(theme).build(),
+        )
+        .draw();
+        println!();
+    }
+}
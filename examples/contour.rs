@@ -0,0 +1,23 @@
+use tgraph::{func2, ContourGraph, ContourOptions};
+
+/// Inspect the loss landscape of Himmelblau's function with contour lines,
+/// once with automatic levels and once with hand-picked ones
+fn main() {
+    let domain = ((-5f64, 5f64), (-5f64, 5f64)).into();
+    let himmelblau = || func2!(|x, y| (x * x + y - 11f64).powi(2) + (x + y * y - 7f64).powi(2));
+
+    ContourGraph::new(himmelblau(), domain, 80, Some(25)).draw();
+    println!();
+
+    ContourGraph::with_options(
+        himmelblau(),
+        domain,
+        80,
+        Some(25),
+        ContourOptions::builder()
+            .levels(vec![5f64, 20f64, 50f64, 100f64, 200f64])
+            .character('•'.into())
+            .build(),
+    )
+    .draw();
+}
@@ -0,0 +1,22 @@
+use tgraph::{Expression, Graph, MultiGraph};
+
+/// Graph functions typed as text, and show where an invalid one fails
+fn main() {
+    let expr: Expression = "sin(x/2)*4 + 5".parse().unwrap();
+    Graph::new(expr.function(), 80, None).draw();
+    println!();
+
+    let functions = ["x^2 / 400", "10 + 5cos(x/4)"]
+        .iter()
+        .map(|s| Expression::parse(s).unwrap().function())
+        .collect();
+    MultiGraph::new(functions, 80, Some(20)).draw();
+    println!();
+
+    let source = "sqrt(x) * (2 + )";
+    if let Err(error) = Expression::parse(source) {
+        println!("{}", source);
+        println!("{:>width$}", "^", width = error.column);
+        println!("{}", error);
+    }
+}
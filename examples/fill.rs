@@ -0,0 +1,30 @@
+use tgraph::{func, Color, Fill, Graph, GraphOptions, MultiGraph, MultiGraphOptions, Theme};
+
+/// Shade the area under a curve, and the band between two curves
+fn main() {
+    Graph::with_options(
+        func!(|x| f64::sin(x / 8f64).abs() * 6f64),
+        80,
+        None,
+        GraphOptions::builder()
+            .color(Color::Cyan.into())
+            .fill(Fill::Character('.'.into()))
+            .build(),
+        Theme::default(),
+    )
+    .draw();
+    println!();
+
+    MultiGraph::with_options(
+        vec![
+            func!(|x| 8f64 + f64::sin(x / 6f64) * 2f64),
+            func!(|x| 3f64 + f64::sin(x / 6f64)),
+        ],
+        80,
+        None,
+        MultiGraphOptions::builder()
+            .fill_between(vec![(0, 1, Fill::Background(Color::DarkGrey.into()))])
+            .build(),
+    )
+    .draw();
+}
@@ -0,0 +1,18 @@
+use tgraph::{func, MultiGraph};
+
+/// Draw two functions, their sum and a shifted and scaled copy of one of them
+fn main() {
+    let wave = || func!(|x| (x / 6f64).sin() * 3f64 + 4f64);
+    let ramp = || func!(|x| x / 10f64);
+    MultiGraph::new(
+        vec![
+            wave().boxed(),
+            ramp().boxed(),
+            wave() + ramp(),
+            wave().shift_x(20f64).scale_y(0.5),
+        ],
+        80,
+        Some(16),
+    )
+    .draw();
+}
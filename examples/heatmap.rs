@@ -0,0 +1,21 @@
+use tgraph::{func2, Heatmap, HeatmapOptions, Shading};
+
+/// Draw z = sin(x) * cos(y) as a heatmap, first with background colors
+/// and then with shade characters
+fn main() {
+    let domain = ((-3f64, 3f64), (-3f64, 3f64)).into();
+
+    Heatmap::new(func2!(|x, y| f64::sin(x) * f64::cos(y)), domain, 80, None).draw();
+    println!();
+
+    Heatmap::with_options(
+        func2!(|x, y| (-(x * x + y * y) / 4f64).exp()),
+        domain,
+        80,
+        None,
+        HeatmapOptions::builder()
+            .shading(Shading::Characters)
+            .build(),
+    )
+    .draw();
+}
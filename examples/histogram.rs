@@ -0,0 +1,27 @@
+use tgraph::{func, Binning, Histogram, HistogramOptions};
+
+/// Draw the distribution of some samples binned with the Freedman–Diaconis rule
+/// and overlay the normal density they were drawn from
+fn main() {
+    // Deterministic, roughly normal samples (sum of uniforms), mean 0 and standard deviation 1
+    let mut seed = 42u64;
+    let mut uniform = move || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (seed >> 33) as f64 / (1u64 << 31) as f64
+    };
+    let samples: Vec<f64> = (0..500)
+        .map(|_| (0..12).map(|_| uniform()).sum::<f64>() - 6.0)
+        .collect();
+
+    Histogram::with_options(
+        &samples,
+        80,
+        HistogramOptions::builder()
+            .binning(Binning::FreedmanDiaconis)
+            .build(),
+    )
+    .density(func!(
+        |x| (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt()
+    ))
+    .draw();
+}
@@ -0,0 +1,29 @@
+use tgraph::{func2, Color, GraphOptions, ImplicitGraph, ImplicitOptions};
+
+/// Draw relations that are not functions of x: a circle and an elliptic curve
+fn main() {
+    ImplicitGraph::new(
+        func2!(|x, y| x * x + y * y - 25f64),
+        ((-8f64, 8f64), (-6f64, 6f64)).into(),
+        80,
+        None,
+    )
+    .draw();
+    println!();
+
+    ImplicitGraph::with_options(
+        func2!(|x, y| y * y - (x * x * x - x)),
+        ((-2f64, 3f64), (-4f64, 4f64)).into(),
+        80,
+        Some(20),
+        ImplicitOptions::builder()
+            .graph(
+                GraphOptions::builder()
+                    .color(Color::Green.into())
+                    .character('•'.into())
+                    .build(),
+            )
+            .build(),
+    )
+    .draw();
+}
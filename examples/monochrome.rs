@@ -0,0 +1,38 @@
+use tgraph::{func, ColorSupport, GraphOptions, MultiGraph, MultiGraphOptions, Stack};
+
+/// Without colors, as in log files, series are told apart by their characters and line styles
+fn main() {
+    ColorSupport::None.force();
+    let series = |labels: [&str; 3]| {
+        labels
+            .iter()
+            .map(|label| GraphOptions::builder().label(*label).build())
+            .collect::<Vec<_>>()
+    };
+    MultiGraph::with_options(
+        vec![
+            func!(x -> 10f64 - (x / 5f64)),
+            func!(x -> f64::sin(x / 2f64).abs() * 4f64),
+            func!(x -> x.ln()),
+        ],
+        80,
+        None,
+        series(["decay", "wave", "log"]).into(),
+    )
+    .draw();
+    println!();
+    MultiGraph::with_options(
+        vec![
+            func!(x -> 2f64 + f64::sin(x / 4f64)),
+            func!(x -> 2f64 + f64::cos(x / 4f64)),
+            func!(x -> 1f64),
+        ],
+        80,
+        None,
+        MultiGraphOptions::builder()
+            .series(series(["sin", "cos", "base"]))
+            .stack(Stack::Bar)
+            .build(),
+    )
+    .draw();
+}
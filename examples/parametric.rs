@@ -0,0 +1,40 @@
+use tgraph::{Color, Graph, GraphOptions, Parametric, Theme};
+
+/// Draw a circle of radius 8 centered at (20, 9) and a Lissajous figure,
+/// x coordinates are stretched so they keep their shape in the terminal
+fn main() {
+    Graph::parametric(
+        Parametric::new(
+            |t: f64| (20f64 + 8f64 * t.cos(), 9f64 + 8f64 * t.sin()),
+            0f64,
+            std::f64::consts::TAU,
+            200,
+        ),
+        80,
+        None,
+    )
+    .draw();
+    println!();
+
+    Graph::parametric_with_options(
+        Parametric::new(
+            |t: f64| {
+                (
+                    20f64 + 18f64 * (3f64 * t).sin(),
+                    9f64 + 8f64 * (2f64 * t).sin(),
+                )
+            },
+            0f64,
+            std::f64::consts::TAU,
+            600,
+        ),
+        80,
+        None,
+        GraphOptions::builder()
+            .color(Color::Green.into())
+            .character('•'.into())
+            .build(),
+        Theme::default(),
+    )
+    .draw();
+}
@@ -0,0 +1,19 @@
+use tgraph::{func, Color, GraphOptions, PolarGraph, PolarOptions};
+
+/// Draw a four-petal rose curve, r = 3 cos(2θ), on polar axes
+fn main() {
+    PolarGraph::with_options(
+        func!(|theta| 3f64 * f64::cos(2f64 * theta)),
+        80,
+        None,
+        PolarOptions::builder()
+            .graph(
+                GraphOptions::builder()
+                    .color(Color::Magenta.into())
+                    .character('•'.into())
+                    .build(),
+            )
+            .build(),
+    )
+    .draw();
+}
@@ -1,14 +1,15 @@
-use tgraph::{func, Color, Graph, GraphOptions};
+use tgraph::{func, Color, Graph, GraphOptions, Theme};
 
 /// Generate a graph with a single function in it
 /// the func! macro defines the function we want to display
-/// `Graph::with_options_screen` allow us to define how we want our graph to appear with the second parameter
+/// `Graph::with_options_screen` allow us to define how we want our graph to appear with the second parameter, and its colors with the theme
 fn main() {
     Graph::with_options_screen(
         func!(|x| 0.005 * (x * x) + 0.1 * x),
         GraphOptions::builder()
             .color(Color::DarkMagenta.into())
             .build(),
+        Theme::default(),
     )
     .draw();
 }
@@ -0,0 +1,24 @@
+use tgraph::{func, Sparkline, SparklineOptions};
+
+/// Sparklines are plain strings, so they can be embedded anywhere
+fn main() {
+    let cpu = [12, 18, 35, 71, 64, 30, 22, 15, 40, 88, 93, 51];
+    println!("cpu  {}", Sparkline::new(cpu, 12));
+    println!(
+        "mem  {}",
+        Sparkline::with_options(
+            vec![Some(1.2), Some(1.4), None, Some(2.1), Some(2.0), Some(2.6)],
+            12,
+            SparklineOptions::builder().markers(true).build(),
+        )
+    );
+    println!(
+        "sin  {}",
+        Sparkline::function(
+            func!(|x| f64::sin(x)),
+            0f64,
+            2f64 * std::f64::consts::PI,
+            24
+        )
+    );
+}
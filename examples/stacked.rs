@@ -0,0 +1,30 @@
+use tgraph::{func, MultiGraph, MultiGraphOptions, Stack};
+
+/// Stack the resource usage of three services on top of each other,
+/// first as areas and then as bars
+fn main() {
+    let usage = || {
+        vec![
+            func!(|x| 3f64 + f64::sin(x / 10f64) * 2f64),
+            func!(|x| 2f64 + f64::cos(x / 7f64)),
+            func!(|x| 1f64 + x / 40f64),
+        ]
+    };
+
+    MultiGraph::with_options(
+        usage(),
+        80,
+        None,
+        MultiGraphOptions::builder().stack(Stack::Area).build(),
+    )
+    .draw();
+    println!();
+
+    MultiGraph::with_options(
+        usage(),
+        80,
+        None,
+        MultiGraphOptions::builder().stack(Stack::Bar).build(),
+    )
+    .draw();
+}
@@ -0,0 +1,20 @@
+use std::thread::sleep;
+use std::time::Duration;
+use tgraph::{StreamingChart, StreamingOptions};
+
+/// Push a noisy signal to a chart that keeps the last 60 values, redrawing it in place 20 times per second
+fn main() {
+    let mut chart = StreamingChart::with_options(
+        60,
+        80,
+        Some(12),
+        StreamingOptions::builder().refresh_rate(20).build(),
+    );
+    for i in 0..300 {
+        let t = i as f64 / 10f64;
+        chart.push(t.sin() * 40f64 + (t * 7f64).cos() * 8f64 + 50f64);
+        sleep(Duration::from_millis(10));
+    }
+    chart.flush();
+    println!();
+}
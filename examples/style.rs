@@ -0,0 +1,33 @@
+use tgraph::{func, MultiGraph, MultiGraphOptions};
+
+/// Chart style as it could be read from a config file
+const STYLE: &str = r##"
+fill_between = [[0, 1, { character = { custom = "." } }]]
+
+[[series]]
+color = "dark_cyan"
+character = "bullet"
+extrema = true
+
+[[series]]
+color = "#ff8000"
+character = { custom = "o" }
+height_legend = false
+"##;
+
+/// Draw two functions with a style loaded from TOML, and print it back
+fn main() {
+    let options: MultiGraphOptions = toml::from_str(STYLE).unwrap();
+    MultiGraph::with_options(
+        vec![
+            func!(|x| (x / 6f64).sin() * 4f64 + 10f64),
+            func!(|x| (x / 9f64).cos() * 3f64 + 5f64),
+        ],
+        80,
+        None,
+        toml::from_str(STYLE).unwrap(),
+    )
+    .draw();
+    println!();
+    println!("{}", toml::to_string(&options).unwrap());
+}
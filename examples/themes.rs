@@ -0,0 +1,27 @@
+use tgraph::{func, MultiGraph, MultiGraphOptions, Theme};
+
+/// Draw the same functions with each built-in theme
+fn main() {
+    let themes = [
+        ("default", Theme::default()),
+        ("monochrome", Theme::monochrome()),
+        ("colorblind", Theme::colorblind()),
+        ("solarized", Theme::solarized()),
+        ("high contrast", Theme::high_contrast()),
+    ];
+    for (name, theme) in themes {
+        println!("{}", name);
+        MultiGraph::with_options(
+            vec![
+                func!(|x| (x / 5f64).sin() * 4f64 + 6f64),
+                func!(|x| (x / 7f64).cos() * 3f64 + 5f64),
+                func!(|x| x / 10f64 + 1f64),
+            ],
+            60,
+            None,
+            MultiGraphOptions::builder().theme(theme).build(),
+        )
+        .draw();
+        println!();
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::{Duration, SystemTime};
+use tgraph::{TimeSeries, TimeSeriesOptions};
+
+/// Draw a day of hourly metrics from Unix timestamps, and the last minutes of a metric sampled every few seconds
+fn main() {
+    let start = 1_700_000_000u64;
+    let requests: Vec<(u64, f64)> = (0..24)
+        .map(|h| {
+            let t = start + h * 3600;
+            (
+                t,
+                50f64 + 40f64 * (h as f64 / 24f64 * std::f64::consts::TAU).sin(),
+            )
+        })
+        .collect();
+    let errors: Vec<(u64, f64)> = requests.iter().map(|(t, v)| (*t, v / 4f64)).collect();
+    TimeSeries::new(vec![requests, errors], 80, Some(16)).draw();
+    println!();
+
+    let now = SystemTime::now();
+    let cpu: Vec<(SystemTime, f64)> = (0..60)
+        .map(|i| {
+            (
+                now - Duration::from_secs(5 * (60 - i)),
+                (i as f64 / 6f64).sin().abs() * 100f64,
+            )
+        })
+        .collect();
+    TimeSeries::with_options(
+        vec![cpu],
+        80,
+        Some(12),
+        TimeSeriesOptions::builder()
+            .y_domain((0f64, 100f64))
+            .build(),
+    )
+    .draw();
+}
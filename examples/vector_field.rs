@@ -0,0 +1,29 @@
+use tgraph::{Color, VectorField, VectorFieldOptions};
+
+/// Draw the field of a rotating flow colored by magnitude, and the slope field of y' = x - y
+fn main() {
+    VectorField::with_options(
+        |x: f64, y: f64| (-y, x),
+        ((-5f64, 5f64), (-5f64, 5f64)).into(),
+        80,
+        None,
+        VectorFieldOptions::builder()
+            .colors(vec![
+                Color::Blue.into(),
+                Color::Green.into(),
+                Color::Yellow.into(),
+                Color::Red.into(),
+            ])
+            .build(),
+    )
+    .draw();
+    println!();
+
+    VectorField::new(
+        |x: f64, y: f64| (1f64, x - y),
+        ((-3f64, 3f64), (-3f64, 3f64)).into(),
+        80,
+        None,
+    )
+    .draw();
+}
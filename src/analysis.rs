@@ -0,0 +1,264 @@
+use crate::function::{derivative_at, Function};
+use crate::traits::AsF64;
+
+/// Points where the functions are evaluated to look for sign changes, over the whole interval.
+const SCAN_SAMPLES: u32 = 1000;
+/// Maximum iterations of Brent's method, which usually converges in a few dozens.
+const MAX_ITERATIONS: u32 = 100;
+
+/// Whether an [`Extremum`] is a minimum or a maximum.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExtremumKind {
+    Minimum,
+    Maximum,
+}
+
+/// Local minimum or maximum of a function.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Extremum {
+    pub x: f64,
+    pub y: f64,
+    pub kind: ExtremumKind,
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> Function<X, Y, F> {
+    /// Values of x between `a` and `b` where the function crosses zero, found with Brent's method from the sign changes of evenly spaced samples.
+    ///
+    /// ```
+    /// use tgraph::func;
+    ///
+    /// let roots = func!(|x| x * x - 2f64).roots(0f64, 10f64);
+    /// assert!((roots[0] - 2f64.sqrt()).abs() < 1e-9);
+    /// ```
+    pub fn roots(&self, a: f64, b: f64) -> Vec<f64> {
+        roots(|x| self.at(x), a, b)
+    }
+
+    /// Local minima and maxima between `a` and `b`, where the derivative changes its sign.
+    pub fn extrema(&self, a: f64, b: f64) -> Vec<Extremum> {
+        let (step, samples) = scan(a, b);
+        let values: Vec<f64> = samples.iter().map(|x| self.at(*x)).collect();
+        let mut extrema = Vec::new();
+        for i in 1..values.len().saturating_sub(1) {
+            let (before, at, after) = (values[i - 1], values[i], values[i + 1]);
+            let kind = if at > before && at >= after {
+                ExtremumKind::Maximum
+            } else if at < before && at <= after {
+                ExtremumKind::Minimum
+            } else {
+                continue;
+            };
+            // The derivative changes its sign around the sample, the extremum is where it is 0
+            let x = brent(
+                |x| derivative_at(|t| self.at(t), x),
+                samples[i] - step,
+                samples[i] + step,
+            )
+            .unwrap_or(samples[i]);
+            extrema.push(Extremum {
+                x,
+                y: self.at(x),
+                kind,
+            });
+        }
+        extrema
+    }
+
+    /// Points between `a` and `b` where this function and `g` cross.
+    pub fn intersections<X2: AsF64, Y2: AsF64, G: Fn(X2) -> Y2>(
+        &self,
+        g: &Function<X2, Y2, G>,
+        a: f64,
+        b: f64,
+    ) -> Vec<(f64, f64)> {
+        roots(|x| self.at(x) - g.at(x), a, b)
+            .into_iter()
+            .map(|x| (x, self.at(x)))
+            .collect()
+    }
+}
+
+/// Step and evenly spaced points from `a` to `b`, both included.
+fn scan(a: f64, b: f64) -> (f64, Vec<f64>) {
+    let step = (b - a) / SCAN_SAMPLES as f64;
+    (
+        step,
+        (0..=SCAN_SAMPLES).map(|i| a + i as f64 * step).collect(),
+    )
+}
+
+fn roots<F: Fn(f64) -> f64>(f: F, a: f64, b: f64) -> Vec<f64> {
+    let (_, samples) = scan(a, b);
+    let values: Vec<f64> = samples.iter().map(|x| f(*x)).collect();
+    let mut roots = Vec::new();
+    for i in 0..samples.len() {
+        if values[i] == 0f64 {
+            roots.push(samples[i]);
+        } else if i + 1 < samples.len()
+            && values[i + 1] != 0f64
+            && values[i].is_finite()
+            && values[i + 1].is_finite()
+            && (values[i] < 0f64) != (values[i + 1] < 0f64)
+        {
+            if let Some(root) = brent(&f, samples[i], samples[i + 1]) {
+                // Sign changes through a pole, like tan at π/2, aren't roots
+                if f(root).abs() < (values[i].abs()).max(values[i + 1].abs()) {
+                    roots.push(root);
+                }
+            }
+        }
+    }
+    roots
+}
+
+/// Root of `f` in `[a, b]` with Brent's method, `None` if `f` has the same sign at both ends.
+pub(crate) fn brent<F: Fn(f64) -> f64>(f: F, a: f64, b: f64) -> Option<f64> {
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if fa == 0f64 {
+        return Some(a);
+    }
+    if fb == 0f64 {
+        return Some(b);
+    }
+    if !fa.is_finite() || !fb.is_finite() || (fa < 0f64) == (fb < 0f64) {
+        return None;
+    }
+    if fa.abs() < fb.abs() {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut bisected = true;
+    for _ in 0..MAX_ITERATIONS {
+        let tolerance = 2f64 * f64::EPSILON * b.abs().max(1f64);
+        if fb == 0f64 || (b - a).abs() < tolerance {
+            break;
+        }
+        // Inverse quadratic interpolation, or the secant method when two points match
+        let mut s = if fa != fc && fb != fc {
+            a * fb * fc / ((fa - fb) * (fa - fc))
+                + b * fa * fc / ((fb - fa) * (fb - fc))
+                + c * fa * fb / ((fc - fa) * (fc - fb))
+        } else {
+            b - fb * (b - a) / (fb - fa)
+        };
+        // Fall back to bisection when the interpolation is out of bounds or converging slowly
+        let bound = (3f64 * a + b) / 4f64;
+        let out_of_bounds = !((s > bound.min(b)) && (s < bound.max(b)));
+        let slow = if bisected {
+            (s - b).abs() >= (b - c).abs() / 2f64 || (b - c).abs() < tolerance
+        } else {
+            (s - b).abs() >= (c - d).abs() / 2f64 || (c - d).abs() < tolerance
+        };
+        bisected = out_of_bounds || slow;
+        if bisected {
+            s = (a + b) / 2f64;
+        }
+        let fs = f(s);
+        d = c;
+        c = b;
+        fc = fb;
+        if (fa < 0f64) != (fs < 0f64) {
+            b = s;
+            fb = fs;
+        } else {
+            a = s;
+            fa = fs;
+        }
+        if fa.abs() < fb.abs() {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut fa, &mut fb);
+        }
+    }
+    Some(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn roots_at_sign_changes() {
+        let roots = Function::new(f64::sin).roots(-1f64, 10f64);
+        assert_eq!(roots.len(), 4);
+        for (root, k) in roots.iter().zip(0..) {
+            assert!(close(*root, k as f64 * PI, 1e-9), "{root}");
+        }
+
+        let roots = Function::new(|x: f64| x * x - 2f64).roots(-3f64, 3f64);
+        assert_eq!(roots.len(), 2);
+        assert!(close(roots[0], -(2f64.sqrt()), 1e-9));
+        assert!(close(roots[1], 2f64.sqrt(), 1e-9));
+
+        assert!(Function::new(|x: f64| x * x + 1f64)
+            .roots(-3f64, 3f64)
+            .is_empty());
+    }
+
+    #[test]
+    fn roots_on_samples_are_found_once() {
+        let roots = Function::new(|x: f64| x).roots(-1f64, 1f64);
+        assert_eq!(roots.len(), 1);
+        assert!(close(roots[0], 0f64, 1e-12));
+    }
+
+    #[test]
+    fn poles_are_not_roots() {
+        let roots = Function::new(f64::tan).roots(1f64, 5f64);
+        assert_eq!(roots.len(), 1);
+        assert!(close(roots[0], PI, 1e-9));
+    }
+
+    #[test]
+    fn extrema_of_sin() {
+        let extrema = Function::new(f64::sin).extrema(0f64, 2f64 * PI);
+        assert_eq!(extrema.len(), 2);
+        assert_eq!(extrema[0].kind, ExtremumKind::Maximum);
+        assert!(close(extrema[0].x, FRAC_PI_2, 1e-6));
+        assert!(close(extrema[0].y, 1f64, 1e-9));
+        assert_eq!(extrema[1].kind, ExtremumKind::Minimum);
+        assert!(close(extrema[1].x, 3f64 * FRAC_PI_2, 1e-6));
+        assert!(close(extrema[1].y, -1f64, 1e-9));
+    }
+
+    #[test]
+    fn extrema_need_a_change_of_direction() {
+        let extrema = Function::new(|x: f64| (x - 1f64).powi(2)).extrema(-2f64, 3f64);
+        assert_eq!(extrema.len(), 1);
+        assert_eq!(extrema[0].kind, ExtremumKind::Minimum);
+        assert!(close(extrema[0].x, 1f64, 1e-6));
+
+        assert!(Function::new(|x: f64| x.powi(3))
+            .extrema(-2f64, 2f64)
+            .is_empty());
+        // Ends of the interval aren't extrema
+        assert!(Function::new(|x: f64| x).extrema(0f64, 1f64).is_empty());
+    }
+
+    #[test]
+    fn intersections_of_sin_and_cos() {
+        let points = Function::new(f64::sin).intersections(&Function::new(f64::cos), 0f64, PI);
+        assert_eq!(points.len(), 1);
+        assert!(close(points[0].0, FRAC_PI_4, 1e-9));
+        assert!(close(points[0].1, FRAC_PI_4.sin(), 1e-9));
+    }
+
+    #[test]
+    fn brent_needs_a_sign_change() {
+        assert!(close(
+            brent(f64::cos, 0f64, 3f64).unwrap(),
+            FRAC_PI_2,
+            1e-12
+        ));
+        assert_eq!(brent(|x| x - 2f64, 0f64, 2f64), Some(2f64));
+        assert_eq!(brent(|x| x * x + 1f64, -1f64, 1f64), None);
+        assert_eq!(brent(|x| 1f64 / x, 0f64, 1f64), None);
+    }
+}
@@ -0,0 +1,88 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+
+use crate::analysis::ExtremumKind;
+use crate::bar_chart::format_value;
+use crate::types::ColorWrapper;
+
+pub(crate) const ROOT_MARKER: char = '○';
+pub(crate) const MAXIMUM_MARKER: char = '▲';
+pub(crate) const MINIMUM_MARKER: char = '▼';
+pub(crate) const INTERSECTION_MARKER: char = '◆';
+
+/// Point of interest marked on a chart, labelled with its coordinates.
+pub(crate) struct Annotation {
+    /// Screen position of the marker.
+    pub position: (i32, i32),
+    pub marker: char,
+    pub color: ColorWrapper,
+    /// Coordinates of the point in the units of the function.
+    pub point: (f64, f64),
+}
+
+impl Annotation {
+    pub fn label(&self) -> String {
+        format!(
+            "({}, {})",
+            format_value(self.point.0),
+            format_value(self.point.1)
+        )
+    }
+}
+
+pub(crate) fn extremum_marker(kind: ExtremumKind) -> char {
+    match kind {
+        ExtremumKind::Maximum => MAXIMUM_MARKER,
+        ExtremumKind::Minimum => MINIMUM_MARKER,
+    }
+}
+
+/// Draws the markers, on top of everything else, and their labels next to them where they only cover blank cells between columns `left` and `right`, rows `top` and `bottom`.
+///
+/// Labels that don't fit around their marker are left out, the marker is still drawn.
+pub(crate) fn draw_annotations(
+    scr: &mut Screen,
+    annotations: &[Annotation],
+    (left, right): (i32, i32),
+    (top, bottom): (i32, i32),
+) {
+    for annotation in annotations {
+        let (x, y) = annotation.position;
+        if x < left || x > right || y < top || y > bottom {
+            continue;
+        }
+        scr.set_pxl(
+            x,
+            y,
+            pixel::pxl_fg(annotation.marker, annotation.color.into()),
+        );
+    }
+    for annotation in annotations {
+        let (x, y) = annotation.position;
+        if x < left || x > right || y < top || y > bottom {
+            continue;
+        }
+        let label = annotation.label();
+        let len = label.chars().count() as i32;
+        // Right of the marker, then left of it, then across it, on its row and then the closest rows
+        let place = [y, y - 1, y + 1, y - 2, y + 2, y - 3, y + 3]
+            .into_iter()
+            .flat_map(|row| {
+                [x + 2, x - 1 - len]
+                    .into_iter()
+                    .chain((x - len..x + 2).rev())
+                    .map(move |start| (start, row))
+            })
+            .find(|(start, row)| {
+                *row >= top
+                    && *row <= bottom
+                    && *start >= left
+                    && start + len - 1 <= right
+                    && (*start..start + len)
+                        .all(|c| scr.get_pxl(c, *row).is_ok_and(|p| p.chr == ' '))
+            });
+        if let Some((start, row)) = place {
+            scr.print(start, row, &label);
+        }
+    }
+}
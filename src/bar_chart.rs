@@ -0,0 +1,238 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use derivative::Derivative;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::color::draw_screen;
+use crate::theme::Theme;
+use crate::traits::AsF64;
+use crate::types::ColorWrapper;
+
+/// Partial block characters used for the last cell of a bar, indexed by eighths (1 to 7).
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const FULL_BLOCK: char = '█';
+
+#[derive(Derivative, TypedBuilder, Clone, Debug)]
+#[derivative(Default)]
+pub struct BarChartOptions {
+    #[builder(default)]
+    pub color: ColorWrapper,
+    /// Print the numeric value of each bar after its end.
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub value_legend: bool,
+    /// Colors of the axis, labels, values and background. The bars use `color`.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+/// Horizontal bar chart, one row per bar with its label left-aligned in a column before the bar.
+/// Bars of negative values are empty, their value legend still shows the value.
+pub struct BarChart {
+    bars: Vec<(String, f64)>,
+    widths: BarChartWidths,
+    max: f64,
+    pub(crate) options: BarChartOptions,
+}
+
+pub struct BarChartWidths {
+    pub total: u32,
+    pub label: u32,
+    pub bar: u32,
+    pub value_legend: u32,
+}
+
+impl BarChart {
+    /// `width` refers to the total width of the chart, including the label column and the value legend.
+    pub fn new<L: Into<String>, V: AsF64>(bars: Vec<(L, V)>, width: u32) -> BarChart {
+        BarChart::with_options(bars, width, BarChartOptions::default())
+    }
+
+    pub fn new_screen<L: Into<String>, V: AsF64>(bars: Vec<(L, V)>) -> BarChart {
+        BarChart::with_options_screen(bars, BarChartOptions::default())
+    }
+
+    pub fn with_options<L: Into<String>, V: AsF64>(
+        bars: Vec<(L, V)>,
+        width: u32,
+        options: BarChartOptions,
+    ) -> BarChart {
+        let bars: Vec<(String, f64)> = bars
+            .into_iter()
+            .map(|(label, value)| (label.into(), value.as_f64()))
+            .collect();
+        // Get max value, bars are scaled relative to it
+        let max = bars.iter().map(|(_, v)| *v).fold(0f64, f64::max);
+        // Labels can take at most a third of the chart, longer ones get truncated
+        let label = (bars
+            .iter()
+            .map(|(l, _)| l.chars().count() as u32)
+            .max()
+            .unwrap_or_default())
+        .min(width / 3);
+        let value_legend = if options.value_legend {
+            bars.iter()
+                .map(|(_, v)| format_value(*v).chars().count() as u32 + 1)
+                .max()
+                .unwrap_or_default()
+        } else {
+            0
+        };
+        BarChart {
+            widths: BarChartWidths {
+                total: width,
+                label,
+                bar: width.saturating_sub(label + 1 + value_legend),
+                value_legend,
+            },
+            bars,
+            max,
+            options,
+        }
+    }
+
+    pub fn with_options_screen<L: Into<String>, V: AsF64>(
+        bars: Vec<(L, V)>,
+        options: BarChartOptions,
+    ) -> BarChart {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        BarChart::with_options(bars, w_screen as u32, options)
+    }
+
+    /// Makes room for values up to `max` (e.g. overlays drawn on top of the bars) when it is larger than the highest bar.
+    pub(crate) fn fit_max(&mut self, max: f64) {
+        self.max = self.max.max(max);
+    }
+
+    /// Column of the screen holding the last cell of a bar of `value`.
+    pub(crate) fn column_of(&self, value: f64) -> i32 {
+        let cells = if self.max > 0f64 {
+            value.max(0f64) / self.max * self.widths.bar as f64
+        } else {
+            0f64
+        };
+        self.widths.label as i32 + 1 + (cells.ceil() as i32 - 1).max(0)
+    }
+
+    pub(crate) fn render(&self) -> Screen {
+        let mut scr = Screen::new(self.widths.total, self.bars.len() as u32);
+
+        self.draw_labels(&mut scr);
+        self.draw_axis(&mut scr);
+        self.draw_bars(&mut scr);
+
+        scr
+    }
+
+    pub fn draw(&self) {
+        let mut scr = self.render();
+        self.options.theme.paint_background(&mut scr);
+        draw_screen(scr);
+    }
+
+    fn draw_labels(&self, scr: &mut Screen) {
+        for (row, (label, _)) in self.bars.iter().enumerate() {
+            self.options.theme.print_legend(
+                scr,
+                0,
+                row as i32,
+                &truncate_label(label, self.widths.label),
+            );
+        }
+    }
+
+    fn draw_axis(&self, scr: &mut Screen) {
+        scr.v_line(
+            self.widths.label as i32,
+            0,
+            self.bars.len() as i32,
+            pixel::pxl_fg('|', self.options.theme.axis.into()),
+        );
+    }
+
+    fn draw_bars(&self, scr: &mut Screen) {
+        let start = self.widths.label as i32 + 1;
+        for (row, (_, value)) in self.bars.iter().enumerate() {
+            let eighths = if self.max > 0f64 {
+                (value.max(0f64) / self.max * self.widths.bar as f64 * 8f64).round() as u32
+            } else {
+                0
+            };
+            let (full, partial) = (eighths / 8, eighths % 8);
+            for x in 0..full {
+                scr.set_pxl(
+                    start + x as i32,
+                    row as i32,
+                    pixel::pxl_fg(FULL_BLOCK, self.options.color.into()),
+                );
+            }
+            if partial > 0 {
+                scr.set_pxl(
+                    start + full as i32,
+                    row as i32,
+                    pixel::pxl_fg(PARTIAL_BLOCKS[partial as usize], self.options.color.into()),
+                );
+            }
+            if self.options.value_legend {
+                let end = start + full as i32 + (partial > 0) as i32;
+                self.options
+                    .theme
+                    .print_legend(scr, end + 1, row as i32, &format_value(*value));
+            }
+        }
+    }
+}
+
+impl fmt::Display for BarChart {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+/// Formats a value for legends: integers without decimals, everything else with at most two.
+///
+/// ```
+/// use tgraph::format_value;
+///
+/// assert_eq!(format_value(2f64), "2");
+/// assert_eq!(format_value(0.126), "0.13");
+/// assert_eq!(format_value(1.50), "1.5");
+/// ```
+pub fn format_value(v: f64) -> String {
+    if v.fract() == 0f64 {
+        format!("{}", v)
+    } else {
+        let s = format!("{:.2}", v);
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// Cuts `label` to `width` characters, marking the cut with an ellipsis.
+fn truncate_label(label: &str, width: u32) -> String {
+    if label.chars().count() as u32 <= width {
+        label.to_string()
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut truncated: String = label.chars().take(width as usize - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::plain_text;
+
+    #[test]
+    fn negative_values_keep_their_label() {
+        let chart = BarChart::new(vec![("a", 4f64), ("b", -5f64)], 10);
+        let text = plain_text(&chart.render());
+        let rows: Vec<&str> = text.lines().collect();
+        assert_eq!(rows[0].trim_end(), "a|█████ 4");
+        assert_eq!(rows[1].trim_end(), "b| -5");
+    }
+}
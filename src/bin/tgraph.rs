@@ -0,0 +1,390 @@
+//! Command line interface of `tgraph`, plotting numbers or CSV columns read from a file or the standard input.
+//!
+//! Built with the `cli` feature: `cargo install tgraph --features cli`.
+
+use clap::{Parser, ValueEnum};
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::iter::successors;
+use std::process::exit;
+use tgraph::{
+    format_value, BarChart, BarChartOptions, Character, ColorWrapper, Function, GraphOptions,
+    Histogram, HistogramOptions, MultiGraph, Sparkline, Theme, TimeSeries, TimeSeriesOptions,
+};
+
+/// Height of the plotting area when `--height` isn't given.
+const DEFAULT_HEIGHT: u32 = 20;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Kind {
+    /// Line graph of each series over its row number, values are scaled to the height
+    Line,
+    /// Bar chart of the first series, labelled with the x column
+    Bar,
+    /// Histogram of the values of the first series
+    Hist,
+    /// One sparkline per series
+    Spark,
+    /// Series over the x column, read as Unix timestamps in seconds
+    Time,
+}
+
+/// Plot numbers or CSV columns in the terminal.
+///
+/// Each line of the input is a row, its fields separated by commas (or whitespace if the first row has no commas). A first row without any number is read as a header with the names of the columns.
+#[derive(Parser, Debug)]
+#[command(name = "tgraph", version)]
+struct Args {
+    /// File to read, the standard input if missing or `-`
+    file: Option<String>,
+    /// Kind of chart
+    #[arg(short, long, value_enum, default_value_t = Kind::Line)]
+    kind: Kind,
+    /// Columns to plot, by name or 1-based index, e.g. `-s cpu,mem` or `-s 2,3`. All numeric columns but the x column if missing
+    #[arg(short, long, value_delimiter = ',')]
+    series: Vec<String>,
+    /// Column holding the labels of `bar` charts or the timestamps of `time` charts, by name or 1-based index
+    #[arg(short = 'x', long, default_value = "1")]
+    x_column: String,
+    /// Total width of the chart, the width of the terminal if missing
+    #[arg(short, long)]
+    width: Option<u32>,
+    /// Height of the plotting area
+    #[arg(short = 'H', long)]
+    height: Option<u32>,
+    /// Color of each series, e.g. `red,blue,dark_green` or `#ff8000`
+    #[arg(short, long, value_delimiter = ',', value_parser = |s: &str| s.parse::<ColorWrapper>())]
+    colors: Vec<ColorWrapper>,
+    /// Character of each series, e.g. `*,+,o`
+    #[arg(short = 'C', long, value_delimiter = ',')]
+    characters: Vec<char>,
+    /// Field separator, detected from the first row if missing
+    #[arg(short, long)]
+    delimiter: Option<char>,
+}
+
+/// Rows of the input, split in fields, and the names of the columns if there is a header.
+struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn parse(input: &str, delimiter: Option<char>) -> Table {
+        let lines: Vec<&str> = input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect();
+        let delimiter =
+            delimiter.or_else(|| lines.first().and_then(|l| l.contains(',').then_some(',')));
+        let split = |line: &str| -> Vec<String> {
+            match delimiter {
+                Some(d) => line
+                    .split(d)
+                    .map(|f| f.trim().trim_matches('"').to_string())
+                    .collect(),
+                None => line.split_whitespace().map(str::to_string).collect(),
+            }
+        };
+        let mut rows: Vec<Vec<String>> = lines.into_iter().map(split).collect();
+        let header = match rows.first() {
+            Some(first) if first.iter().all(|f| number(f).is_none()) => Some(rows.remove(0)),
+            _ => None,
+        };
+        Table { header, rows }
+    }
+
+    /// Index of the column given by name or 1-based index.
+    fn column(&self, name: &str) -> Result<usize, String> {
+        if let Some(i) = self
+            .header
+            .as_ref()
+            .and_then(|h| h.iter().position(|c| c == name))
+        {
+            return Ok(i);
+        }
+        match name.parse::<usize>() {
+            Ok(i) if i >= 1 => Ok(i - 1),
+            _ => Err(format!("unknown column `{}`", name)),
+        }
+    }
+
+    fn name(&self, column: usize) -> String {
+        self.header
+            .as_ref()
+            .and_then(|h| h.get(column).cloned())
+            .unwrap_or_else(|| (column + 1).to_string())
+    }
+
+    fn columns(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or_default()
+    }
+
+    /// Values of a column, `None` where a row is too short or its field isn't a number.
+    fn values(&self, column: usize) -> Vec<Option<f64>> {
+        self.rows
+            .iter()
+            .map(|r| r.get(column).and_then(|f| number(f)))
+            .collect()
+    }
+
+    fn text(&self, column: usize) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|r| r.get(column).cloned().unwrap_or_default())
+            .collect()
+    }
+}
+
+fn number(field: &str) -> Option<f64> {
+    field.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(e) = run(args) {
+        eprintln!("tgraph: {}", e);
+        exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let input = match args.file.as_deref() {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("can't read the standard input: {}", e))?;
+            input
+        }
+        Some(path) => {
+            fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path, e))?
+        }
+    };
+    let table = Table::parse(&input, args.delimiter);
+    if table.rows.is_empty() {
+        return Err("no data to plot".to_string());
+    }
+
+    let x_column = table.column(&args.x_column)?;
+    let uses_x = matches!(args.kind, Kind::Bar | Kind::Time);
+    let series: Vec<usize> = if args.series.is_empty() {
+        (0..table.columns())
+            .filter(|c| !(uses_x && *c == x_column))
+            .filter(|c| table.values(*c).iter().any(Option::is_some))
+            .collect()
+    } else {
+        args.series
+            .iter()
+            .map(|s| table.column(s))
+            .collect::<Result<_, _>>()?
+    };
+    if series.is_empty() {
+        return Err("no numeric column to plot".to_string());
+    }
+
+    let width = args.width.unwrap_or_else(|| {
+        if io::stdout().is_terminal() {
+            console_engine::crossterm::terminal::size()
+                .map(|(w, _)| w as u32)
+                .unwrap_or(80)
+        } else {
+            80
+        }
+    });
+    let theme = Theme::default();
+    let graph_options: Vec<GraphOptions> = (0..series.len())
+        .map(|i| GraphOptions {
+            color: args.colors.get(i).copied().unwrap_or(theme.color(i)),
+            character: args
+                .characters
+                .get(i)
+                .map_or(Character::default(), |c| (*c).into()),
+            label: (series.len() > 1).then(|| table.name(series[i])),
+            ..GraphOptions::default()
+        })
+        .collect();
+
+    match args.kind {
+        Kind::Line => line(&table, &series, width, args.height, graph_options),
+        Kind::Bar => {
+            let labels = table.text(x_column);
+            let bars: Vec<(String, f64)> = labels
+                .into_iter()
+                .zip(table.values(series[0]))
+                .filter_map(|(label, v)| v.map(|v| (label, v)))
+                .collect();
+            let options = BarChartOptions::builder()
+                .color(graph_options[0].color)
+                .build();
+            BarChart::with_options(bars, width, options).draw();
+        }
+        Kind::Hist => {
+            let options = HistogramOptions::builder()
+                .bars(
+                    BarChartOptions::builder()
+                        .color(graph_options[0].color)
+                        .build(),
+                )
+                .build();
+            Histogram::with_options(table.values(series[0]), width, options).draw();
+        }
+        Kind::Spark => {
+            let names: Vec<String> = series.iter().map(|c| table.name(*c)).collect();
+            let label_width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+            for (column, name) in series.iter().zip(names) {
+                let spark_width = (width as usize).saturating_sub(label_width + 1).max(1);
+                println!(
+                    "{:<w$} {}",
+                    name,
+                    Sparkline::new(table.values(*column), spark_width as u32),
+                    w = label_width
+                );
+            }
+            return Ok(());
+        }
+        Kind::Time => {
+            let times = table.values(x_column);
+            let points: Vec<Vec<(f64, f64)>> = series
+                .iter()
+                .map(|c| {
+                    times
+                        .iter()
+                        .zip(table.values(*c))
+                        .filter_map(|(t, v)| Some(((*t)?, v?)))
+                        .collect()
+                })
+                .collect();
+            let options = TimeSeriesOptions::builder().series(graph_options).build();
+            TimeSeries::with_options(points, width, args.height, options).draw();
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Draws the series with [`MultiGraph`], which plots values in rows over columns: the rows of the table are spread over the columns and values are scaled so that the maximum reaches the top. Rows are counted from the smallest value if there are negative ones, from 0 otherwise, so the row numbers of the height legend are hidden and the range of values is printed above the chart instead.
+fn line(
+    table: &Table,
+    series: &[usize],
+    width: u32,
+    height: Option<u32>,
+    graph_options: Vec<GraphOptions>,
+) {
+    let height = height.unwrap_or(DEFAULT_HEIGHT).max(2);
+    let top = (height - 1) as f64;
+    // Columns left by the height legend of MultiGraph
+    let digits = successors(Some(height - 1), |&n| (n >= 10).then_some(n / 10)).count() as u32;
+    let columns = width.saturating_sub(digits).max(1) as usize;
+    let values = column_values(table, series, columns);
+    let (min, max) = values
+        .iter()
+        .flatten()
+        .filter(|v| v.is_finite())
+        .fold((0f64, 0f64), |(min, max), v| (min.min(*v), max.max(*v)));
+    let scale = if max > min { top / (max - min) } else { 1f64 };
+
+    let functions = values
+        .into_iter()
+        .map(|v| {
+            Function::new(move |x: u32| {
+                (v.get(x as usize).copied().unwrap_or(f64::NAN) - min) * scale
+            })
+        })
+        .collect();
+    println!(
+        "{} to {}, 1 row = {}",
+        format_value(min),
+        format_value(max),
+        format_value(1f64 / scale)
+    );
+    let graph_options: Vec<GraphOptions> = graph_options
+        .into_iter()
+        .map(|o| GraphOptions {
+            height_legend: false,
+            ..o
+        })
+        .collect();
+    MultiGraph::with_options(functions, width, Some(height), graph_options.into()).draw();
+}
+
+/// Values of each series at each of `columns` columns, with the rows of the table spread evenly over them. Rows without a value give NaN, which isn't drawn, so that all series stay aligned with the rows.
+fn column_values(table: &Table, series: &[usize], columns: usize) -> Vec<Vec<f64>> {
+    let rows = table.rows.len();
+    series
+        .iter()
+        .map(|c| {
+            let v = table.values(*c);
+            (0..columns)
+                .map(|x| match rows {
+                    0 => f64::NAN,
+                    _ => v[(x * rows / columns).min(rows - 1)].unwrap_or(f64::NAN),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_csv_with_header() {
+        let table = Table::parse("time,cpu,mem\n# comment\n\n1,0.5,\"20\"\n2,0.7\n", None);
+        assert_eq!(
+            table.header,
+            Some(vec![
+                "time".to_string(),
+                "cpu".to_string(),
+                "mem".to_string()
+            ])
+        );
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.columns(), 3);
+        assert_eq!(table.values(2), vec![Some(20f64), None]);
+        assert_eq!(table.name(1), "cpu");
+    }
+
+    #[test]
+    fn parse_whitespace_without_header() {
+        let table = Table::parse("1 2\n3   nan\n", None);
+        assert!(table.header.is_none());
+        assert_eq!(table.values(0), vec![Some(1f64), Some(3f64)]);
+        // Non-finite values aren't numbers to plot
+        assert_eq!(table.values(1), vec![Some(2f64), None]);
+        assert_eq!(table.name(1), "2");
+    }
+
+    #[test]
+    fn parse_with_delimiter() {
+        let table = Table::parse("a;b\n1;2\n", Some(';'));
+        assert_eq!(table.text(1), vec!["2".to_string()]);
+    }
+
+    #[test]
+    fn column_by_name_or_index() {
+        let table = Table::parse("x,y\n1,2\n", None);
+        assert_eq!(table.column("y"), Ok(1));
+        assert_eq!(table.column("1"), Ok(0));
+        assert!(table.column("0").is_err());
+        assert!(table.column("z").is_err());
+    }
+
+    #[test]
+    fn gaps_keep_series_aligned() {
+        let table = Table::parse("1,1\n2,\n3,3\n4,4\n", None);
+        let values = column_values(&table, &[0, 1], 8);
+        assert_eq!(
+            values[0],
+            vec![1f64, 1f64, 2f64, 2f64, 3f64, 3f64, 4f64, 4f64]
+        );
+        assert_eq!(values[1][..2], [1f64, 1f64]);
+        assert!(values[1][2].is_nan() && values[1][3].is_nan());
+        assert_eq!(values[1][4..], [3f64, 3f64, 4f64, 4f64]);
+        assert!(column_values(&Table::parse("", None), &[0], 2)[0]
+            .iter()
+            .all(|v| v.is_nan()));
+    }
+}
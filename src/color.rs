@@ -0,0 +1,432 @@
+use console_engine::crossterm::style::{ResetColor, SetBackgroundColor, SetForegroundColor};
+use console_engine::crossterm::Command;
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use console_engine::Color;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use unicode_width::UnicodeWidthChar;
+
+use crate::types::ColorWrapper;
+
+/// Colors the terminal can show, [`ColorSupport::detect`]ed once and then kept, unless set with [`ColorSupport::force`].
+static SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+const UNKNOWN: u8 = u8::MAX;
+
+/// Values of the 16 named colors in the default palette of xterm, in the order of the first 16 colors of the 256-color palette.
+const NAMED_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (128, 0, 0)),
+    (Color::DarkGreen, (0, 128, 0)),
+    (Color::DarkYellow, (128, 128, 0)),
+    (Color::DarkBlue, (0, 0, 128)),
+    (Color::DarkMagenta, (128, 0, 128)),
+    (Color::DarkCyan, (0, 128, 128)),
+    (Color::Grey, (192, 192, 192)),
+    (Color::DarkGrey, (128, 128, 128)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (0, 0, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+/// Characters standing for background colors when charts are drawn without colors, from the darkest color to the brightest.
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+/// Levels of each channel in the 6×6×6 color cube of the 256-color palette, colors 16 to 231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Colors a terminal can show, charts drawn in colors it can't show are downgraded to the closest ones it can.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// No colors, everything is drawn in the color of the terminal text
+    None,
+    /// The 16 named colors
+    Basic,
+    /// The 256-color palette
+    Ansi256,
+    /// 24-bit RGB colors
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Colors charts are drawn with, detected from the environment the first time.
+    pub fn current() -> ColorSupport {
+        match SUPPORT.load(Ordering::Relaxed) {
+            UNKNOWN => {
+                let support = ColorSupport::detect();
+                SUPPORT.store(support as u8, Ordering::Relaxed);
+                support
+            }
+            support => ColorSupport::from_u8(support),
+        }
+    }
+
+    /// Draws the following charts with these colors, whatever the terminal supports, even when the output isn't a terminal.
+    pub fn force(self) {
+        SUPPORT.store(self as u8, Ordering::Relaxed);
+    }
+
+    /// Colors supported by the terminal according to the environment variables, see [`ColorSupport::from_env`], or none if the output isn't a terminal, like when it goes to a pipe or a log file.
+    pub fn detect() -> ColorSupport {
+        if !io::stdout().is_terminal() {
+            return ColorSupport::None;
+        }
+        ColorSupport::from_env(|name| env::var(name).ok())
+    }
+
+    /// Colors supported according to the environment variables given by `var`: none if `NO_COLOR` is set (and not empty) or `TERM` is `dumb`, RGB if `COLORTERM` is `truecolor` or `24bit`, 256 colors if `TERM` has `256color` in it, and the 16 named colors otherwise.
+    ///
+    /// ```
+    /// use tgraph::ColorSupport;
+    ///
+    /// let env = |vars: &'static [(&'static str, &'static str)]| {
+    ///     move |name: &str| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+    /// };
+    /// assert_eq!(ColorSupport::from_env(env(&[("COLORTERM", "truecolor")])), ColorSupport::TrueColor);
+    /// assert_eq!(ColorSupport::from_env(env(&[("TERM", "xterm-256color")])), ColorSupport::Ansi256);
+    /// assert_eq!(ColorSupport::from_env(env(&[("TERM", "xterm"), ("NO_COLOR", "1")])), ColorSupport::None);
+    /// ```
+    pub fn from_env<F: Fn(&str) -> Option<String>>(var: F) -> ColorSupport {
+        let term = var("TERM").unwrap_or_default();
+        if var("NO_COLOR").is_some_and(|v| !v.is_empty()) || term == "dumb" {
+            ColorSupport::None
+        } else if var("COLORTERM").is_some_and(|v| v == "truecolor" || v == "24bit") {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Basic
+        }
+    }
+
+    fn from_u8(v: u8) -> ColorSupport {
+        match v {
+            0 => ColorSupport::None,
+            1 => ColorSupport::Basic,
+            2 => ColorSupport::Ansi256,
+            _ => ColorSupport::TrueColor,
+        }
+    }
+}
+
+impl ColorWrapper {
+    pub fn rgb(r: u8, g: u8, b: u8) -> ColorWrapper {
+        Color::Rgb { r, g, b }.into()
+    }
+
+    /// Color `v` of the 256-color palette.
+    pub fn ansi(v: u8) -> ColorWrapper {
+        Color::AnsiValue(v).into()
+    }
+
+    /// Red, green and blue values of the color, `None` for the color of the terminal (`Color::Reset`).
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        match Color::from(self) {
+            Color::Reset => None,
+            Color::Rgb { r, g, b } => Some((r, g, b)),
+            Color::AnsiValue(v) => Some(ansi_to_rgb(v)),
+            color => NAMED_COLORS
+                .iter()
+                .find(|(c, _)| *c == color)
+                .map(|(_, rgb)| *rgb),
+        }
+    }
+
+    /// Closest color that can be shown with `support`.
+    ///
+    /// ```
+    /// use tgraph::{Color, ColorSupport, ColorWrapper};
+    ///
+    /// let orange = ColorWrapper::rgb(255, 135, 0);
+    /// assert_eq!(orange.downgrade(ColorSupport::Ansi256).to_string(), "ansi(208)");
+    /// assert_eq!(Color::from(orange.downgrade(ColorSupport::Basic)), Color::DarkYellow);
+    /// assert_eq!(Color::from(orange.downgrade(ColorSupport::None)), Color::Reset);
+    /// ```
+    pub fn downgrade(self, support: ColorSupport) -> ColorWrapper {
+        let color = Color::from(self);
+        match (support, color) {
+            (ColorSupport::None, _) => Color::Reset.into(),
+            (ColorSupport::TrueColor, _) | (_, Color::Reset) => self,
+            (ColorSupport::Ansi256, Color::Rgb { r, g, b }) => {
+                ColorWrapper::ansi(rgb_to_ansi((r, g, b)))
+            }
+            (ColorSupport::Ansi256, _) => self,
+            (ColorSupport::Basic, Color::Rgb { .. } | Color::AnsiValue(_)) => {
+                let rgb = self.to_rgb().unwrap();
+                NAMED_COLORS
+                    .iter()
+                    .min_by_key(|(_, named)| distance(rgb, *named))
+                    .map(|(c, _)| (*c).into())
+                    .unwrap()
+            }
+            (ColorSupport::Basic, _) => self,
+        }
+    }
+
+    /// `n` colors evenly spaced from `from` to `to`, both included, mixed as RGB colors.
+    ///
+    /// ```
+    /// use tgraph::{Color, ColorWrapper};
+    ///
+    /// let gradient = ColorWrapper::gradient(Color::Black.into(), Color::White.into(), 3);
+    /// assert_eq!(gradient[1].to_string(), "#808080");
+    /// ```
+    pub fn gradient(from: ColorWrapper, to: ColorWrapper, n: usize) -> Vec<ColorWrapper> {
+        let (from, to) = (
+            from.to_rgb().unwrap_or_default(),
+            to.to_rgb().unwrap_or_default(),
+        );
+        let mix = |a: u8, b: u8, t: f64| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        (0..n)
+            .map(|i| {
+                let t = if n > 1 {
+                    i as f64 / (n - 1) as f64
+                } else {
+                    0f64
+                };
+                ColorWrapper::rgb(
+                    mix(from.0, to.0, t),
+                    mix(from.1, to.1, t),
+                    mix(from.2, to.2, t),
+                )
+            })
+            .collect()
+    }
+}
+
+fn ansi_to_rgb(v: u8) -> (u8, u8, u8) {
+    match v {
+        0..=15 => NAMED_COLORS[v as usize].1,
+        16..=231 => {
+            let i = (v - 16) as usize;
+            (
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[i / 6 % 6],
+                CUBE_LEVELS[i % 6],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (v - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Closest color of the 256-color palette, in the color cube or the grayscale ramp.
+fn rgb_to_ansi(rgb: (u8, u8, u8)) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|i| (CUBE_LEVELS[*i] as i32 - c as i32).abs())
+            .unwrap()
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as usize;
+    [cube as u8, gray as u8]
+        .into_iter()
+        .min_by_key(|v| distance(rgb, ansi_to_rgb(*v)))
+        .unwrap()
+}
+
+/// Squared distance between two colors, weighted by how sensitive the eye is to each channel.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8, weight: u32| weight * (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0, 3) + d(a.1, b.1, 4) + d(a.2, b.2, 2)
+}
+
+/// Draws the screen with its colors downgraded to the ones supported by the terminal.
+///
+/// Without colors, like by default when the output isn't a terminal, only the characters are printed, without escape codes, so that charts can be piped or written to log files.
+pub(crate) fn draw_screen(mut scr: Screen) {
+    let support = ColorSupport::current();
+    let mut stdout = io::stdout();
+    if support == ColorSupport::None {
+        write!(stdout, "{}", plain_text(&scr)).unwrap();
+        stdout.flush().unwrap();
+        return;
+    }
+    if support != ColorSupport::TrueColor {
+        for y in 0..scr.get_height() as i32 {
+            for x in 0..scr.get_width() as i32 {
+                if let Ok(p) = scr.get_pxl(x, y) {
+                    let fg = ColorWrapper::from(p.fg).downgrade(support);
+                    let bg = ColorWrapper::from(p.bg).downgrade(support);
+                    scr.set_pxl(x, y, pixel::pxl_fbg(p.chr, fg.into(), bg.into()));
+                }
+            }
+        }
+    }
+    write!(stdout, "{}", colored_text(&scr)).unwrap();
+    stdout.flush().unwrap();
+}
+
+/// Cells of a row of the screen, leaving out the ones covered by the wide character before them.
+fn row_cells(scr: &Screen, y: i32) -> Vec<pixel::Pixel> {
+    let mut cells = Vec::with_capacity(scr.get_width() as usize);
+    let mut skip_next = false;
+    for x in 0..scr.get_width() as i32 {
+        let p = scr.get_pxl(x, y).unwrap_or_else(|_| pixel::pxl(' '));
+        if std::mem::take(&mut skip_next) {
+            continue;
+        }
+        skip_next = UnicodeWidthChar::width(p.chr).is_some_and(|w| w > 1);
+        cells.push(p);
+    }
+    cells
+}
+
+/// Characters of the screen, a line per row without its trailing blanks, and no line break after the last one, like [`Screen::draw`]. Blank cells shown only by their background color get a shade of it instead.
+pub(crate) fn plain_text(scr: &Screen) -> String {
+    (0..scr.get_height() as i32)
+        .map(|y| {
+            let row: String = row_cells(scr, y)
+                .into_iter()
+                .map(|p| match p.chr {
+                    ' ' => shade(p.bg.into()).unwrap_or(' '),
+                    chr => chr,
+                })
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Characters of the screen with the escape codes of their colors, a line per row ending with the colors reset, and no line break after the last one. Unlike [`Screen::draw`], it doesn't need the output to be a terminal.
+pub(crate) fn colored_text(scr: &Screen) -> String {
+    let mut text = String::new();
+    for y in 0..scr.get_height() as i32 {
+        if y > 0 {
+            text.push('\n');
+        }
+        let mut colors = None;
+        for p in row_cells(scr, y) {
+            if colors != Some((p.fg, p.bg)) {
+                SetForegroundColor(p.fg).write_ansi(&mut text).unwrap();
+                SetBackgroundColor(p.bg).write_ansi(&mut text).unwrap();
+                colors = Some((p.fg, p.bg));
+            }
+            text.push(p.chr);
+        }
+        ResetColor.write_ansi(&mut text).unwrap();
+    }
+    text
+}
+
+/// Shade standing for background color `bg` without colors, by its brightness. `None` for the color of the terminal.
+fn shade(bg: ColorWrapper) -> Option<char> {
+    let (r, g, b) = bg.to_rgb()?;
+    let brightness = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64) / 255f64;
+    Some(SHADES[(brightness * (SHADES.len() - 1) as f64).round() as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bar_chart::BarChart;
+
+    #[test]
+    fn plain_text_has_no_escapes() {
+        let chart = BarChart::new(vec![("a", 1f64), ("bb", 2f64)], 20);
+        let text = plain_text(&chart.render());
+        assert!(!text.contains('\x1b'));
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.lines().all(|row| !row.ends_with(' ')));
+        assert!(!text.ends_with('\n'));
+    }
+
+    #[test]
+    fn plain_text_skips_cells_covered_by_wide_characters() {
+        let mut scr = Screen::new(4, 1);
+        scr.print(0, 0, "界ab");
+        assert_eq!(plain_text(&scr), "界b");
+    }
+
+    #[test]
+    fn plain_text_shades_backgrounds() {
+        let mut scr = Screen::new(4, 1);
+        scr.set_pxl(0, 0, pixel::pxl_bg(' ', Color::Black));
+        scr.set_pxl(1, 0, pixel::pxl_bg(' ', Color::White));
+        scr.set_pxl(2, 0, pixel::pxl_bg('x', Color::White));
+        assert_eq!(plain_text(&scr), "░█x");
+    }
+
+    #[test]
+    fn colored_text_has_escapes_without_a_terminal() {
+        let mut scr = Screen::new(3, 2);
+        scr.set_pxl(0, 0, pixel::pxl_fg('a', Color::Red));
+        scr.set_pxl(1, 0, pixel::pxl_fg('b', Color::Red));
+        let text = colored_text(&scr);
+        assert_eq!(text.matches("\x1b[38;5;9m").count(), 1);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.ends_with("\x1b[0m"));
+    }
+
+    #[test]
+    fn rgb_to_ansi_picks_cube_or_gray() {
+        assert_eq!(rgb_to_ansi((0, 0, 0)), 16);
+        assert_eq!(rgb_to_ansi((255, 0, 0)), 196);
+        assert_eq!(rgb_to_ansi((255, 135, 0)), 208);
+        assert_eq!(rgb_to_ansi((128, 128, 128)), 244);
+    }
+
+    #[test]
+    fn rgb_to_ansi_inverts_ansi_to_rgb() {
+        for v in 16..=255 {
+            assert_eq!(ansi_to_rgb(rgb_to_ansi(ansi_to_rgb(v))), ansi_to_rgb(v));
+        }
+    }
+
+    #[test]
+    fn downgrade() {
+        let red = ColorWrapper::rgb(250, 10, 10);
+        assert_eq!(
+            Color::from(red.downgrade(ColorSupport::TrueColor)),
+            Color::from(red)
+        );
+        assert_eq!(
+            Color::from(red.downgrade(ColorSupport::Ansi256)),
+            Color::AnsiValue(196)
+        );
+        assert_eq!(Color::from(red.downgrade(ColorSupport::Basic)), Color::Red);
+        assert_eq!(Color::from(red.downgrade(ColorSupport::None)), Color::Reset);
+        assert_eq!(
+            Color::from(ColorWrapper::ansi(21).downgrade(ColorSupport::Basic)),
+            Color::Blue
+        );
+        for support in [ColorSupport::Basic, ColorSupport::Ansi256] {
+            let cyan = ColorWrapper::from(Color::DarkCyan);
+            assert_eq!(Color::from(cyan.downgrade(support)), Color::DarkCyan);
+            let reset = ColorWrapper::from(Color::Reset);
+            assert_eq!(Color::from(reset.downgrade(support)), Color::Reset);
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(ColorSupport::from_env(env(&[])), ColorSupport::Basic);
+        assert_eq!(
+            ColorSupport::from_env(env(&[("COLORTERM", "24bit")])),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(env(&[("TERM", "dumb"), ("COLORTERM", "truecolor")])),
+            ColorSupport::None
+        );
+        // An empty NO_COLOR doesn't count
+        assert_eq!(
+            ColorSupport::from_env(env(&[("NO_COLOR", ""), ("TERM", "screen-256color")])),
+            ColorSupport::Ansi256
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use derivative::Derivative;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::bar_chart::format_value;
+use crate::color::draw_screen;
+use crate::function::Function2;
+use crate::heatmap::default_gradient;
+use crate::implicit::{marching_squares, sample_grid};
+use crate::plane::Plane;
+use crate::theme::Theme;
+use crate::traits::AsF64;
+use crate::types::{Character, ColorWrapper, Domain};
+
+/// Values of `z` at which the iso-lines of a [`ContourGraph`] are drawn.
+#[derive(Clone, Debug)]
+pub enum Levels {
+    /// Evenly spaced levels strictly between the minimum and maximum of the function. **(Default)** with 6 levels
+    Count(u32),
+    /// Exactly these levels
+    Values(Vec<f64>),
+}
+
+impl Default for Levels {
+    fn default() -> Levels {
+        Levels::Count(6)
+    }
+}
+
+impl Levels {
+    /// Levels to draw for a function whose values are between `min` and `max`.
+    pub fn values(&self, min: f64, max: f64) -> Vec<f64> {
+        let count = match self {
+            Levels::Values(values) => return values.clone(),
+            Levels::Count(n) => *n,
+        };
+        (1..=count)
+            .map(|i| min + i as f64 * (max - min) / (count + 1) as f64)
+            .collect()
+    }
+}
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct ContourOptions {
+    #[builder(default, setter(into))]
+    pub levels: Levels,
+    /// Colors from the lowest to the highest level, levels in between get the nearest one.
+    #[derivative(Default(value = "default_gradient()"))]
+    #[builder(default = default_gradient())]
+    pub colors: Vec<ColorWrapper>,
+    #[builder(default)]
+    pub character: Character,
+    /// Number of samples per cell side, higher values find thinner features of the iso-lines.
+    #[derivative(Default(value = "4"))]
+    #[builder(default = 4)]
+    pub resolution: u32,
+    /// List the value of each level, in its color, on the right.
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub level_legend: bool,
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub height_legend: bool,
+    /// Colors of the axis, legends, background and grid. The iso-lines use `colors`.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+impl From<Vec<f64>> for Levels {
+    fn from(values: Vec<f64>) -> Levels {
+        Levels::Values(values)
+    }
+}
+
+/// Contour plot of a function of two variables `z = f(x, y)` over a [`Domain`], with an iso-line in a different color for each level.
+pub struct ContourGraph {
+    plane: Plane,
+    /// Each level with the screen cells its iso-line crosses.
+    lines: Vec<(f64, Vec<(i32, i32)>)>,
+    width: u32,
+    options: ContourOptions,
+}
+
+impl ContourGraph {
+    /// `width` includes the level legend. `set_height` is the height of the plotting area, by default the one keeping the same scale on both axes.
+    pub fn new<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+    ) -> ContourGraph {
+        ContourGraph::with_options(f, domain, width, set_height, ContourOptions::default())
+    }
+
+    pub fn new_screen<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+    ) -> ContourGraph {
+        ContourGraph::with_options_screen(f, domain, ContourOptions::default())
+    }
+
+    pub fn with_options<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+        options: ContourOptions,
+    ) -> ContourGraph {
+        let resolution = options.resolution.max(1);
+        let (plane, values, levels) = if options.level_legend {
+            // The width of the legend depends on the levels, unless they are given take them from the values over the whole width
+            let levels = match options.levels {
+                Levels::Values(_) => sorted_levels(&options.levels, &[]),
+                Levels::Count(_) => sorted_levels(
+                    &options.levels,
+                    &sample_grid(&Plane::new(domain, width, set_height), 1, |x, y| f.at(x, y)),
+                ),
+            };
+            let legend = 2 + levels
+                .iter()
+                .map(|l| format_value(*l).chars().count() as u32 + 2)
+                .max()
+                .unwrap_or_default();
+            let plane = Plane::new(domain, width.saturating_sub(legend), set_height);
+            let values = sample_grid(&plane, resolution, |x, y| f.at(x, y));
+            (plane, values, levels)
+        } else {
+            let plane = Plane::new(domain, width, set_height);
+            let values = sample_grid(&plane, resolution, |x, y| f.at(x, y));
+            let levels = sorted_levels(&options.levels, &values);
+            (plane, values, levels)
+        };
+        let lines = levels
+            .into_iter()
+            .map(|level| (level, marching_squares(&plane, resolution, &values, level)))
+            .collect();
+        ContourGraph {
+            plane,
+            lines,
+            width,
+            options,
+        }
+    }
+
+    pub fn with_options_screen<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        options: ContourOptions,
+    ) -> ContourGraph {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        ContourGraph::with_options(f, domain, w_screen as u32, None, options)
+    }
+
+    pub fn draw(&self) {
+        let mut scr = Screen::new(self.width, self.plane.height);
+
+        self.plane.draw_axis(&mut scr, &self.options.theme);
+        if self.options.height_legend {
+            self.plane.draw_legends(&mut scr, &self.options.theme);
+        }
+        self.draw_lines(&mut scr);
+        if self.options.level_legend {
+            self.draw_level_legend(&mut scr);
+        }
+
+        self.options.theme.paint_background(&mut scr);
+
+        draw_screen(scr);
+    }
+
+    /// Color of the `i`-th level.
+    fn color(&self, i: usize) -> ColorWrapper {
+        let colors = &self.options.colors;
+        if colors.is_empty() {
+            return ColorWrapper::default();
+        }
+        let t = if self.lines.len() > 1 {
+            i as f64 / (self.lines.len() - 1) as f64
+        } else {
+            0f64
+        };
+        colors[(t * (colors.len() - 1) as f64).round() as usize]
+    }
+
+    fn draw_lines(&self, scr: &mut Screen) {
+        for (i, (_, cells)) in self.lines.iter().enumerate() {
+            let pxl = pixel::pxl_fg(self.options.character.as_char(), self.color(i).into());
+            for (x, y) in cells {
+                scr.set_pxl(*x, *y, pxl);
+            }
+        }
+    }
+
+    /// Lists the levels from the highest at the top to the lowest.
+    fn draw_level_legend(&self, scr: &mut Screen) {
+        let x = self.plane.widths.total as i32 + 2;
+        for (row, (i, (level, _))) in self.lines.iter().enumerate().rev().enumerate() {
+            scr.set_pxl(
+                x,
+                row as i32,
+                pixel::pxl_fg(self.options.character.as_char(), self.color(i).into()),
+            );
+            self.options
+                .theme
+                .print_legend(scr, x + 2, row as i32, &format_value(*level));
+        }
+    }
+}
+
+impl fmt::Display for ContourGraph {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+/// Levels for a function sampled as `values`, sorted from the lowest.
+fn sorted_levels(levels: &Levels, values: &[Vec<f64>]) -> Vec<f64> {
+    let (min, max) = values
+        .iter()
+        .flatten()
+        .filter(|z| z.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), z| {
+            (min.min(*z), max.max(*z))
+        });
+    let mut levels = levels.values(min, max);
+    levels.retain(|l| l.is_finite());
+    levels.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Evaluations of the function to build a contour graph with `options`, and evaluations of a single pass over its plane.
+    fn evaluations(options: ContourOptions) -> (u32, u32) {
+        let count = AtomicU32::new(0);
+        let f = Function2::new(|x: f64, y: f64| {
+            count.fetch_add(1, Ordering::Relaxed);
+            x * x + y * y
+        });
+        let domain = ((-1f64, 1f64), (-1f64, 1f64)).into();
+        let resolution = options.resolution;
+        let graph = ContourGraph::with_options(f, domain, 40, Some(10), options);
+        let pass = AtomicU32::new(0);
+        sample_grid(&graph.plane, resolution, |_, _| {
+            pass.fetch_add(1, Ordering::Relaxed);
+            0f64
+        });
+        (count.into_inner(), pass.into_inner())
+    }
+
+    #[test]
+    fn samples_once_without_estimate() {
+        let (count, pass) = evaluations(
+            ContourOptions::builder()
+                .levels(Levels::Values(vec![0.5, 1f64]))
+                .build(),
+        );
+        assert_eq!(count, pass);
+        let (count, pass) = evaluations(ContourOptions::builder().level_legend(false).build());
+        assert_eq!(count, pass);
+    }
+
+    #[test]
+    fn lines_use_the_levels_of_the_legend() {
+        let graph = ContourGraph::with_options(
+            Function2::new(|x: f64, y: f64| x * x + y * y),
+            ((-1f64, 1f64), (-1f64, 1f64)).into(),
+            40,
+            Some(10),
+            ContourOptions::builder().levels(Levels::Count(3)).build(),
+        );
+        assert_eq!(graph.lines.len(), 3);
+        assert!(graph.lines.windows(2).all(|l| l[0].0 < l[1].0));
+    }
+}
@@ -0,0 +1,550 @@
+use std::error::Error;
+use std::f64::consts::{E, PI, TAU};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::function::{Function, Function2};
+
+/// Implementation of a function callable in an [`Expression`], taking its arguments.
+type Builtin = fn(&[f64]) -> f64;
+
+/// Functions that can be called in an [`Expression`], with their number of arguments.
+const FUNCTIONS: [(&str, usize, Builtin); 22] = [
+    ("sin", 1, |a| a[0].sin()),
+    ("cos", 1, |a| a[0].cos()),
+    ("tan", 1, |a| a[0].tan()),
+    ("asin", 1, |a| a[0].asin()),
+    ("acos", 1, |a| a[0].acos()),
+    ("atan", 1, |a| a[0].atan()),
+    ("sinh", 1, |a| a[0].sinh()),
+    ("cosh", 1, |a| a[0].cosh()),
+    ("tanh", 1, |a| a[0].tanh()),
+    ("exp", 1, |a| a[0].exp()),
+    ("ln", 1, |a| a[0].ln()),
+    ("log", 1, |a| a[0].log10()),
+    ("log2", 1, |a| a[0].log2()),
+    ("sqrt", 1, |a| a[0].sqrt()),
+    ("abs", 1, |a| a[0].abs()),
+    ("floor", 1, |a| a[0].floor()),
+    ("ceil", 1, |a| a[0].ceil()),
+    ("round", 1, |a| a[0].round()),
+    ("sign", 1, |a| a[0].signum()),
+    ("min", 2, |a| a[0].min(a[1])),
+    ("max", 2, |a| a[0].max(a[1])),
+    ("atan2", 2, |a| a[0].atan2(a[1])),
+];
+
+const CONSTANTS: [(&str, f64); 3] = [("pi", PI), ("e", E), ("tau", TAU)];
+
+/// Levels of parentheses, calls, signs and exponents an [`Expression`] can nest, so that parsing can't overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Error found while parsing an [`Expression`], at a 1-based `column` of the source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{} at column {}", self.message, self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Mathematical expression parsed from a string such as `sin(x/2)*4 + x^2`, to be graphed as a [`Function`].
+///
+/// Expressions support numbers (`2`, `0.5`, `1e-3`), the operators `+ - * / ^` (`^` being right associative and binding tighter than a leading `-`), parentheses, the constants `pi`, `e` and `tau`, and the functions `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `sinh`, `cosh`, `tanh`, `exp`, `ln`, `log` (base 10), `log2`, `sqrt`, `abs`, `floor`, `ceil`, `round`, `sign`, `min`, `max` and `atan2`. A number right before a variable, a function or a parenthesis multiplies it, as in `2x` or `3(x + 1)`. Parentheses, calls, signs and exponents nesting more than 256 levels deep are an error.
+///
+/// ```
+/// use tgraph::Expression;
+///
+/// let expr: Expression = "sin(x/2)*4 + x^2".parse().unwrap();
+/// assert_eq!(expr.at(&[0f64]), 0f64);
+///
+/// let error = Expression::parse("2 * (x + )").unwrap_err();
+/// assert_eq!(error.column, 10);
+/// assert_eq!(error.to_string(), "expected a value, found `)` at column 10");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Expression {
+    root: Node,
+    variables: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Number(f64),
+    /// Index of the variable in [`Expression::variables`].
+    Variable(usize),
+    Negate(Box<Node>),
+    Binary(char, Box<Node>, Box<Node>),
+    /// Operators of the same precedence applied from the left, `a - b + c` being `a` and `[('-', b), ('+', c)]`. Kept flat so that long sums and products don't nest.
+    Chain(Box<Node>, Vec<(char, Node)>),
+    Call(Builtin, Vec<Node>),
+}
+
+impl Expression {
+    /// Parses an expression of the single variable `x`.
+    pub fn parse(source: &str) -> Result<Expression, ParseError> {
+        Expression::parse_with(source, &["x"])
+    }
+
+    /// Parses an expression of the given variables, any other name is an error.
+    pub fn parse_with(source: &str, variables: &[&str]) -> Result<Expression, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+            variables,
+            end: source.chars().count() + 1,
+            depth: 0,
+        };
+        let root = parser.expression()?;
+        if let Some(token) = parser.peek() {
+            return Err(token.error(format!("unexpected {}", token.kind)));
+        }
+        Ok(Expression {
+            root,
+            variables: variables.iter().map(|v| v.to_string()).collect(),
+        })
+    }
+
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// Value of the expression, `values` holding the value of each variable in the order they were given when parsing.
+    pub fn at(&self, values: &[f64]) -> f64 {
+        self.root.eval(values)
+    }
+
+    /// Function of the first variable, for [`Graph`](crate::Graph) and [`MultiGraph`](crate::MultiGraph).
+    pub fn function(self) -> Function<f64, f64, impl Fn(f64) -> f64> {
+        Function::new(move |x: f64| self.at(&[x]))
+    }
+
+    /// Function of the first two variables, for the charts over a [`Domain`](crate::Domain) of the plane.
+    pub fn function2(self) -> Function2<f64, f64, f64, impl Fn(f64, f64) -> f64> {
+        Function2::new(move |x: f64, y: f64| self.at(&[x, y]))
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ParseError;
+
+    fn from_str(source: &str) -> Result<Expression, ParseError> {
+        Expression::parse(source)
+    }
+}
+
+impl Node {
+    fn eval(&self, values: &[f64]) -> f64 {
+        match self {
+            Node::Number(n) => *n,
+            Node::Variable(i) => values.get(*i).copied().unwrap_or(f64::NAN),
+            Node::Negate(node) => -node.eval(values),
+            Node::Binary(op, a, b) => apply(*op, a.eval(values), b.eval(values)),
+            Node::Chain(first, rest) => rest.iter().fold(first.eval(values), |a, (op, b)| {
+                apply(*op, a, b.eval(values))
+            }),
+            Node::Call(f, args) => {
+                let args: Vec<f64> = args.iter().map(|a| a.eval(values)).collect();
+                f(&args)
+            }
+        }
+    }
+}
+
+fn apply(op: char, a: f64, b: f64) -> f64 {
+    match op {
+        '+' => a + b,
+        '-' => a - b,
+        '*' => a * b,
+        '/' => a / b,
+        _ => a.powf(b),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            TokenKind::Number(n) => write!(f, "number `{}`", n),
+            TokenKind::Name(name) => write!(f, "`{}`", name),
+            TokenKind::Symbol(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+struct Token {
+    kind: TokenKind,
+    column: usize,
+}
+
+impl Token {
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            column: self.column,
+            message,
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (c, column) = (chars[i], i + 1);
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent, only if digits follow so that `2e` is still 2 times e
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let sign = matches!(chars.get(i + 1), Some('+') | Some('-')) as usize;
+                if chars.get(i + 1 + sign).is_some_and(|d| d.is_ascii_digit()) {
+                    i += 1 + sign;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text.parse::<f64>().map_err(|_| ParseError {
+                column,
+                message: format!("invalid number `{}`", text),
+            })?;
+            tokens.push(Token {
+                kind: TokenKind::Number(n),
+                column,
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Name(chars[start..i].iter().collect()),
+                column,
+            });
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token {
+                kind: TokenKind::Symbol(c),
+                column,
+            });
+            i += 1;
+        } else {
+            return Err(ParseError {
+                column,
+                message: format!("unexpected character `{}`", c),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+/// `first` alone, or followed by the operators and operands of `rest`.
+fn chain(first: Node, rest: Vec<(char, Node)>) -> Node {
+    if rest.is_empty() {
+        first
+    } else {
+        Node::Chain(Box::new(first), rest)
+    }
+}
+
+/// Recursive descent parser, one method per precedence level from the lowest.
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    variables: &'a [&'a str],
+    /// Column right after the source, where errors about a missing token point.
+    end: usize,
+    /// Levels currently nested, see [`MAX_DEPTH`].
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next_symbol_is(&self, symbol: char) -> bool {
+        self.peek()
+            .is_some_and(|t| t.kind == TokenKind::Symbol(symbol))
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(t) if t.kind == TokenKind::Symbol(symbol) => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(t) => Err(t.error(format!("expected `{}`, found {}", symbol, t.kind))),
+            None => Err(ParseError {
+                column: self.end,
+                message: format!("expected `{}`, found the end of the expression", symbol),
+            }),
+        }
+    }
+
+    /// Sums and differences of terms.
+    fn expression(&mut self) -> Result<Node, ParseError> {
+        let first = self.term()?;
+        let mut rest = Vec::new();
+        while let Some(op) = ['+', '-'].into_iter().find(|op| self.next_symbol_is(*op)) {
+            self.position += 1;
+            rest.push((op, self.term()?));
+        }
+        Ok(chain(first, rest))
+    }
+
+    /// Products and quotients of factors.
+    fn term(&mut self) -> Result<Node, ParseError> {
+        let first = self.unary()?;
+        let mut rest = Vec::new();
+        while let Some(op) = ['*', '/'].into_iter().find(|op| self.next_symbol_is(*op)) {
+            self.position += 1;
+            rest.push((op, self.unary()?));
+        }
+        Ok(chain(first, rest))
+    }
+
+    /// Signed powers. Every nested level goes through here, where the depth is limited.
+    fn unary(&mut self) -> Result<Node, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(ParseError {
+                column: self.peek().map_or(self.end, |t| t.column),
+                message: format!("expression nested more than {} levels deep", MAX_DEPTH),
+            });
+        }
+        self.depth += 1;
+        let node = self.signed();
+        self.depth -= 1;
+        node
+    }
+
+    fn signed(&mut self) -> Result<Node, ParseError> {
+        if self.next_symbol_is('-') {
+            self.position += 1;
+            return Ok(Node::Negate(Box::new(self.unary()?)));
+        }
+        if self.next_symbol_is('+') {
+            self.position += 1;
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Node, ParseError> {
+        let base = self.implicit_product()?;
+        if self.next_symbol_is('^') {
+            self.position += 1;
+            // Right associative, and the exponent may have a sign: `2^-x`
+            return Ok(Node::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    /// A number directly followed by a name or a parenthesis multiplies it.
+    fn implicit_product(&mut self) -> Result<Node, ParseError> {
+        let is_number = matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::Number(_),
+                ..
+            })
+        );
+        let node = self.primary()?;
+        let multiplies = matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::Name(_) | TokenKind::Symbol('('),
+                ..
+            })
+        );
+        if is_number && multiplies {
+            return Ok(Node::Binary('*', Box::new(node), Box::new(self.power()?)));
+        }
+        Ok(node)
+    }
+
+    fn primary(&mut self) -> Result<Node, ParseError> {
+        let token = match self.tokens.get(self.position) {
+            Some(token) => token,
+            None => {
+                return Err(ParseError {
+                    column: self.end,
+                    message: "expected a value, found the end of the expression".to_string(),
+                })
+            }
+        };
+        let (kind, column) = (token.kind.clone(), token.column);
+        self.position += 1;
+        match kind {
+            TokenKind::Number(n) => Ok(Node::Number(n)),
+            TokenKind::Symbol('(') => {
+                let node = self.expression()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            TokenKind::Name(name) if self.next_symbol_is('(') => self.call(&name, column),
+            TokenKind::Name(name) => {
+                if let Some(i) = self.variables.iter().position(|v| *v == name) {
+                    Ok(Node::Variable(i))
+                } else if let Some((_, value)) = CONSTANTS.iter().find(|(c, _)| *c == name) {
+                    Ok(Node::Number(*value))
+                } else if FUNCTIONS.iter().any(|(f, _, _)| *f == name) {
+                    Err(ParseError {
+                        column,
+                        message: format!("function `{}` must be called with parentheses", name),
+                    })
+                } else {
+                    Err(ParseError {
+                        column,
+                        message: format!("unknown variable `{}`", name),
+                    })
+                }
+            }
+            kind => Err(ParseError {
+                column,
+                message: format!("expected a value, found {}", kind),
+            }),
+        }
+    }
+
+    /// Arguments of a call to function `name` at `column`, the name already consumed.
+    fn call(&mut self, name: &str, column: usize) -> Result<Node, ParseError> {
+        let (_, arity, f) = *FUNCTIONS
+            .iter()
+            .find(|(f, _, _)| *f == name)
+            .ok_or_else(|| ParseError {
+                column,
+                message: format!("unknown function `{}`", name),
+            })?;
+        self.expect('(')?;
+        let mut args = vec![self.expression()?];
+        while self.next_symbol_is(',') {
+            self.position += 1;
+            args.push(self.expression()?);
+        }
+        self.expect(')')?;
+        if args.len() != arity {
+            return Err(ParseError {
+                column,
+                message: format!(
+                    "function `{}` takes {} argument{}, found {}",
+                    name,
+                    arity,
+                    if arity == 1 { "" } else { "s" },
+                    args.len()
+                ),
+            });
+        }
+        Ok(Node::Call(f, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, x: f64) -> f64 {
+        Expression::parse(source).unwrap().at(&[x])
+    }
+
+    fn error(source: &str) -> ParseError {
+        Expression::parse(source).unwrap_err()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3", 0f64), 7f64);
+        assert_eq!(eval("(1 + 2) * 3", 0f64), 9f64);
+        assert_eq!(eval("2 - 3 - 4", 0f64), -5f64);
+        assert_eq!(eval("8 / 4 / 2", 0f64), 1f64);
+        assert_eq!(eval("1 + 2 * 3 ^ 2", 0f64), 19f64);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(eval("2 ^ 3 ^ 2", 0f64), 512f64);
+        assert_eq!(eval("(2 ^ 3) ^ 2", 0f64), 64f64);
+    }
+
+    #[test]
+    fn power_binds_tighter_than_unary_minus() {
+        assert_eq!(eval("-2 ^ 2", 0f64), -4f64);
+        assert_eq!(eval("(-2) ^ 2", 0f64), 4f64);
+        assert_eq!(eval("2 ^ -1", 0f64), 0.5);
+        assert_eq!(eval("-x ^ 2", 3f64), -9f64);
+    }
+
+    #[test]
+    fn implicit_products() {
+        assert_eq!(eval("2x", 3f64), 6f64);
+        assert_eq!(eval("2pi", 0f64), 2f64 * PI);
+        assert_eq!(eval("3(x + 1)", 1f64), 6f64);
+        assert_eq!(eval("2x^2", 3f64), 18f64);
+        assert_eq!(eval("2sin(x)", 0f64), 0f64);
+        assert_eq!(eval("2e", 0f64), 2f64 * E);
+        assert_eq!(eval("2e3", 0f64), 2000f64);
+    }
+
+    #[test]
+    fn wrong_arity() {
+        let e = error("min(1)");
+        assert_eq!(e.column, 1);
+        assert_eq!(e.message, "function `min` takes 2 arguments, found 1");
+        let e = error("x + sin(1, 2)");
+        assert_eq!(e.column, 5);
+        assert_eq!(e.message, "function `sin` takes 1 argument, found 2");
+    }
+
+    #[test]
+    fn error_columns() {
+        assert_eq!(error("x $ 2").column, 3);
+        assert_eq!(error("y + 1").column, 1);
+        assert_eq!(error("2 * foo(x)").column, 5);
+        assert_eq!(error("(x + 1").column, 7);
+        assert_eq!(error("x + ").column, 5);
+        assert_eq!(error("sin x").column, 1);
+        assert_eq!(error("x 2").column, 3);
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = format!("{}x{}", "(".repeat(100), ")".repeat(100));
+        assert_eq!(eval(&nested, 2f64), 2f64);
+
+        let e = error(&"(".repeat(100_000));
+        assert_eq!(e.column, MAX_DEPTH + 1);
+        assert_eq!(e.message, "expression nested more than 256 levels deep");
+        assert_eq!(
+            error(&format!("{}x", "-".repeat(100_000))).column,
+            MAX_DEPTH + 1
+        );
+        assert_eq!(error(&"2^".repeat(100_000)).column, 2 * MAX_DEPTH + 1);
+        // Long sums and products don't nest
+        let sum = format!("{}x", "x+".repeat(200_000));
+        assert_eq!(eval(&sum, 1f64), 200_001f64);
+        let product = format!("{}x", "x*".repeat(200_000));
+        assert_eq!(eval(&product, 1f64), 1f64);
+        assert_eq!(
+            eval(&format!("1{}", "-x*2".repeat(200_000)), 1f64),
+            -399_999f64
+        );
+        assert_eq!(error(&"sin(".repeat(100_000)).column, 4 * MAX_DEPTH + 1);
+    }
+}
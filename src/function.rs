@@ -1,5 +1,9 @@
 use derivative::Derivative;
+use std::collections::HashMap;
+use std::iter::FusedIterator;
 use std::marker::PhantomData;
+use std::ops::{Add, Bound, Div, Mul, Neg, RangeBounds, Sub};
+use std::sync::Mutex;
 
 use crate::traits::AsF64;
 use crate::types::Scales;
@@ -45,6 +49,46 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> Function<X, Y, F> {
             .collect()
     }
 
+    /// Definite integral from `a` to `b`, over [`INTEGRAL_STEPS`] subintervals.
+    ///
+    /// ```
+    /// use tgraph::{func, Integration};
+    ///
+    /// let f = func!(|x| x * x);
+    /// assert!((f.integrate(0f64, 3f64, Integration::Simpson) - 9f64).abs() < 1e-9);
+    /// ```
+    pub fn integrate(&self, a: f64, b: f64, method: Integration) -> f64 {
+        integrate(|x| self.at(x), a, b, method, INTEGRAL_STEPS)
+    }
+
+    /// Same function, remembering the value at each x it is evaluated at, so that expensive functions are computed only once at each point. The values are kept as long as the function.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use tgraph::Function;
+    ///
+    /// let calls = Cell::new(0);
+    /// let f = Function::new(|x: f64| {
+    ///     calls.set(calls.get() + 1);
+    ///     x * x
+    /// })
+    /// .memoize();
+    /// assert_eq!(f.at(3f64) + f.at(3f64), 18f64);
+    /// assert_eq!(calls.get(), 1);
+    /// ```
+    pub fn memoize(self) -> Function<f64, f64, impl Fn(f64) -> f64> {
+        let cache: Mutex<HashMap<u64, f64>> = Mutex::new(HashMap::new());
+        Function::new(move |x: f64| {
+            if let Some(y) = cache.lock().unwrap().get(&x.to_bits()) {
+                return *y;
+            }
+            // Not locked while evaluating, so that other threads can use the cache meanwhile
+            let y = self.at(x);
+            cache.lock().unwrap().insert(x.to_bits(), y);
+            y
+        })
+    }
+
     pub fn rng_x_scale(&self, x_i: u32, x_f: u32, scales: &Scales) -> Vec<(f64, f64)> {
         (x_i..=x_f)
             .map(|x| {
@@ -57,29 +101,357 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> Function<X, Y, F> {
     }
 }
 
-impl<X: AsF64 + Copy, Y: AsF64, F: Fn(X) -> Y> IntoIterator for Function<X, Y, F> {
+/// Number of subintervals of the numerical integrals of [`Function`].
+pub const INTEGRAL_STEPS: u32 = 256;
+
+/// Rule used to integrate functions numerically.
+#[derive(Copy, Clone, Derivative, Debug, PartialEq, Eq)]
+#[derivative(Default)]
+pub enum Integration {
+    /// Trapezoidal rule, exact for straight lines
+    Trapezoid,
+    #[derivative(Default)]
+    /// **(Default)** Simpson's rule, exact for cubics
+    Simpson,
+}
+
+/// Derivative of `f` at `x` with central differences, the step growing with `x` to keep the relative rounding error low.
+pub(crate) fn derivative_at<F: Fn(f64) -> f64>(f: F, x: f64) -> f64 {
+    let h = f64::EPSILON.cbrt() * x.abs().max(1f64);
+    (f(x + h) - f(x - h)) / (2f64 * h)
+}
+
+/// Integral of `f` from `a` to `b` over `steps` subintervals (rounded up to an even number for Simpson's rule).
+pub(crate) fn integrate<F: Fn(f64) -> f64>(
+    f: F,
+    a: f64,
+    b: f64,
+    method: Integration,
+    steps: u32,
+) -> f64 {
+    if a == b {
+        return 0f64;
+    }
+    match method {
+        Integration::Trapezoid => {
+            let steps = steps.max(1);
+            let h = (b - a) / steps as f64;
+            let inner: f64 = (1..steps).map(|i| f(a + i as f64 * h)).sum();
+            h * ((f(a) + f(b)) / 2f64 + inner)
+        }
+        Integration::Simpson => {
+            let steps = steps.max(2).next_multiple_of(2);
+            let h = (b - a) / steps as f64;
+            let inner: f64 = (1..steps)
+                .map(|i| f(a + i as f64 * h) * if i % 2 == 1 { 4f64 } else { 2f64 })
+                .sum();
+            h / 3f64 * (f(a) + f(b) + inner)
+        }
+    }
+}
+
+/// Function of two variables, `z = f(x, y)`.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Function2<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z> {
+    #[derivative(Debug = "ignore")]
+    f: F,
+    #[derivative(Debug = "ignore")]
+    _x: PhantomData<X>,
+    #[derivative(Debug = "ignore")]
+    _y: PhantomData<Y>,
+    #[derivative(Debug = "ignore")]
+    _z: PhantomData<Z>,
+}
+
+impl<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z> Function2<X, Y, Z, F> {
+    pub fn new(f: F) -> Function2<X, Y, Z, F> {
+        Function2 {
+            f,
+            _x: PhantomData,
+            _y: PhantomData,
+            _z: PhantomData,
+        }
+    }
+
+    pub fn at(&self, x: f64, y: f64) -> f64 {
+        (self.f)(X::from_f64(x), Y::from_f64(y)).as_f64()
+    }
+}
+
+/// Values of the function at `x = 0, 1, 2...`, endlessly. See [`Function::sample`] and [`Function::step`] to iterate over a range.
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> IntoIterator for Function<X, Y, F> {
     type Item = Y;
     type IntoIter = FunctionIntoIterator<X, Y, F>;
 
     fn into_iter(self) -> Self::IntoIter {
         FunctionIntoIterator {
             func: self,
-            x: X::from_f64(0_f64),
+            x: 0f64,
         }
     }
 }
 
 pub struct FunctionIntoIterator<X: AsF64, Y: AsF64, F: Fn(X) -> Y> {
     func: Function<X, Y, F>,
-    x: X,
+    /// Counted as `f64` and not as `X`, which could saturate and stop advancing.
+    x: f64,
 }
 
-impl<X: AsF64 + Copy, Y: AsF64, F: Fn(X) -> Y> Iterator for FunctionIntoIterator<X, Y, F> {
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> Iterator for FunctionIntoIterator<X, Y, F> {
     type Item = Y;
     fn next(&mut self) -> Option<Y> {
-        let res = self.func.at(self.x.as_f64());
-        self.x = X::from_f64(self.x.as_f64() + 1.0);
-        Some(Y::from_f64(res))
+        let res = (self.func.f)(X::from_f64(self.x));
+        self.x += 1f64;
+        Some(res)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> Function<X, Y, F> {
+    /// `samples` evenly spaced `(x, y)` points over `range`. Both ends are included in inclusive ranges (`a..=b`), while exclusive ranges (`a..b`) stop one spacing before `b`.
+    ///
+    /// ```
+    /// use tgraph::func;
+    ///
+    /// let f = func!(|x| x * x);
+    /// let pts: Vec<(f64, f64)> = f.sample(-1f64..=1f64, 5).collect();
+    /// assert_eq!(pts, vec![(-1f64, 1f64), (-0.5, 0.25), (0f64, 0f64), (0.5, 0.25), (1f64, 1f64)]);
+    /// assert_eq!(f.sample(0f64..1f64, 4).rev().next(), Some((0.75, 0.5625)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `range` is unbounded.
+    pub fn sample<R: RangeBounds<f64>>(
+        &self,
+        range: R,
+        samples: usize,
+    ) -> FunctionSamples<'_, X, Y, F> {
+        let (start, end, inclusive) = bounds(&range);
+        let spacing = match (inclusive, samples) {
+            (_, 0) | (true, 1) => 0f64,
+            (true, _) => (end - start) / (samples - 1) as f64,
+            (false, _) => (end - start) / samples as f64,
+        };
+        FunctionSamples {
+            func: self,
+            start,
+            step: spacing,
+            front: 0,
+            back: samples,
+        }
+    }
+
+    /// `(x, y)` points over `range`, from its start every `step`, including the end of inclusive ranges if a point falls on it.
+    ///
+    /// ```
+    /// use tgraph::func;
+    ///
+    /// let f = func!(|x| 2f64 * x);
+    /// assert_eq!(f.step(0f64..=1f64, 0.25).len(), 5);
+    /// assert_eq!(f.step(0f64..1f64, 0.25).last(), Some((0.75, 1.5)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `range` is unbounded or `step` isn't positive, or if the number of steps over `range` isn't finite or doesn't fit in a `usize`, e.g. with a subnormal `step`.
+    pub fn step<R: RangeBounds<f64>>(&self, range: R, step: f64) -> FunctionSamples<'_, X, Y, F> {
+        assert!(step > 0f64, "step must be positive");
+        let (start, end, inclusive) = bounds(&range);
+        // Rounding errors shouldn't drop a point that falls on the end
+        let steps = (end - start) / step;
+        assert!(
+            steps.is_finite() && steps < usize::MAX as f64,
+            "too many steps over the range"
+        );
+        let samples = if steps < 0f64 {
+            0
+        } else if inclusive {
+            (steps + STEP_TOLERANCE).floor() as usize + 1
+        } else {
+            (steps - STEP_TOLERANCE).ceil().max(0f64) as usize
+        };
+        FunctionSamples {
+            func: self,
+            start,
+            step,
+            front: 0,
+            back: samples,
+        }
+    }
+}
+
+/// Fraction of a step by which the end of a range can be missed and still count as reached.
+const STEP_TOLERANCE: f64 = 1e-9;
+
+/// Start and end of a range, and whether the end is included.
+fn bounds<R: RangeBounds<f64>>(range: &R) -> (f64, f64, bool) {
+    let start = match range.start_bound() {
+        Bound::Included(start) | Bound::Excluded(start) => *start,
+        Bound::Unbounded => panic!("range must have a start"),
+    };
+    match range.end_bound() {
+        Bound::Included(end) => (start, *end, true),
+        Bound::Excluded(end) => (start, *end, false),
+        Bound::Unbounded => panic!("range must have an end"),
+    }
+}
+
+/// `(x, y)` points of a [`Function`] over a range, created with [`Function::sample`] or [`Function::step`].
+pub struct FunctionSamples<'a, X: AsF64, Y: AsF64, F: Fn(X) -> Y> {
+    func: &'a Function<X, Y, F>,
+    start: f64,
+    step: f64,
+    /// Indices of the next points to yield from each end, `back` excluded.
+    front: usize,
+    back: usize,
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> FunctionSamples<'_, X, Y, F> {
+    fn pt(&self, i: usize) -> (f64, f64) {
+        self.func.pt(self.start + i as f64 * self.step)
+    }
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> Iterator for FunctionSamples<'_, X, Y, F> {
+    type Item = (f64, f64);
+
+    fn next(&mut self) -> Option<(f64, f64)> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.pt(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<(f64, f64)> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> DoubleEndedIterator for FunctionSamples<'_, X, Y, F> {
+    fn next_back(&mut self) -> Option<(f64, f64)> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.pt(self.back))
+    }
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> ExactSizeIterator for FunctionSamples<'_, X, Y, F> {}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> FusedIterator for FunctionSamples<'_, X, Y, F> {}
+
+/// [`Function`] returned when combining functions, with arithmetic operators or methods such as [`Function::compose`].
+///
+/// The closure is boxed, so functions combined in different ways have the same type and can be drawn together in a [`MultiGraph`](crate::MultiGraph).
+///
+/// ```
+/// use tgraph::func;
+///
+/// let f = (func!(|x| x * x) + func!(|x| 2f64 * x)) / 2f64;
+/// assert_eq!(f.at(2f64), 4f64);
+/// assert_eq!((-f.shift_x(1f64)).at(3f64), -4f64);
+/// ```
+pub type BoxedFunction = Function<f64, f64, Box<dyn Fn(f64) -> f64>>;
+
+impl<X: AsF64 + 'static, Y: AsF64 + 'static, F: Fn(X) -> Y + 'static> Function<X, Y, F> {
+    /// Same function with a boxed closure, to share a [`MultiGraph`](crate::MultiGraph) with combined functions.
+    pub fn boxed(self) -> BoxedFunction {
+        Function::new(Box::new(move |x| self.at(x)))
+    }
+
+    /// `self(g(x))`.
+    pub fn compose<X2: AsF64 + 'static, Y2: AsF64 + 'static, G: Fn(X2) -> Y2 + 'static>(
+        self,
+        g: Function<X2, Y2, G>,
+    ) -> BoxedFunction {
+        Function::new(Box::new(move |x| self.at(g.at(x))))
+    }
+
+    /// Moves the graph `dx` to the right: `self(x - dx)`.
+    pub fn shift_x(self, dx: f64) -> BoxedFunction {
+        Function::new(Box::new(move |x| self.at(x - dx)))
+    }
+
+    /// Stretches the graph vertically by `k`: `k * self(x)`.
+    pub fn scale_y(self, k: f64) -> BoxedFunction {
+        Function::new(Box::new(move |x| k * self.at(x)))
+    }
+
+    /// Derivative approximated with central finite differences.
+    pub fn derivative(self) -> BoxedFunction {
+        Function::new(Box::new(move |x| derivative_at(|t| self.at(t), x)))
+    }
+
+    /// Cumulative integral from `from` to `x`, negative for `x < from`.
+    pub fn integral(self, from: f64, method: Integration) -> BoxedFunction {
+        Function::new(Box::new(move |x| self.integrate(from, x, method)))
+    }
+}
+
+/// Pointwise arithmetic operator between two functions, and between a function and a `f64` on either side.
+macro_rules! impl_function_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<X, Y, F, X2, Y2, G> $trait<Function<X2, Y2, G>> for Function<X, Y, F>
+        where
+            X: AsF64 + 'static,
+            Y: AsF64 + 'static,
+            F: Fn(X) -> Y + 'static,
+            X2: AsF64 + 'static,
+            Y2: AsF64 + 'static,
+            G: Fn(X2) -> Y2 + 'static,
+        {
+            type Output = BoxedFunction;
+
+            fn $method(self, rhs: Function<X2, Y2, G>) -> BoxedFunction {
+                Function::new(Box::new(move |x| self.at(x) $op rhs.at(x)))
+            }
+        }
+
+        impl<X: AsF64 + 'static, Y: AsF64 + 'static, F: Fn(X) -> Y + 'static> $trait<f64>
+            for Function<X, Y, F>
+        {
+            type Output = BoxedFunction;
+
+            fn $method(self, rhs: f64) -> BoxedFunction {
+                Function::new(Box::new(move |x| self.at(x) $op rhs))
+            }
+        }
+
+        impl<X: AsF64 + 'static, Y: AsF64 + 'static, F: Fn(X) -> Y + 'static>
+            $trait<Function<X, Y, F>> for f64
+        {
+            type Output = BoxedFunction;
+
+            fn $method(self, rhs: Function<X, Y, F>) -> BoxedFunction {
+                Function::new(Box::new(move |x| self $op rhs.at(x)))
+            }
+        }
+    };
+}
+
+impl_function_op!(Add, add, +);
+impl_function_op!(Sub, sub, -);
+impl_function_op!(Mul, mul, *);
+impl_function_op!(Div, div, /);
+
+impl<X: AsF64 + 'static, Y: AsF64 + 'static, F: Fn(X) -> Y + 'static> Neg for Function<X, Y, F> {
+    type Output = BoxedFunction;
+
+    fn neg(self) -> BoxedFunction {
+        Function::new(Box::new(move |x| -self.at(x)))
     }
 }
 
@@ -119,3 +491,51 @@ macro_rules! func {
         tgraph::Function::new(|$x: f64| -> $yt { $code } as fn(f64) -> $yt)
     };
 }
+
+/// Same as [`func!`], but for [`Function2`] instances of two variables.
+///
+/// ```
+/// use tgraph::func2;
+///
+/// let circle = func2!(|x, y| x * x + y * y - 25f64);
+/// assert_eq!(circle.at(3f64, 4f64), 0f64);
+/// ```
+#[macro_export]
+macro_rules! func2 {
+    (|$x:ident, $y:ident| $code:expr) => {
+        tgraph::Function2::new(|$x: f64, $y: f64| -> f64 { $code } as fn(f64, f64) -> f64)
+    };
+    ($e:expr) => {
+        tgraph::Function2::new($e)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity() -> Function<f64, f64, impl Fn(f64) -> f64> {
+        Function::new(|x: f64| x)
+    }
+
+    #[test]
+    #[should_panic(expected = "too many steps")]
+    fn step_rejects_subnormal_steps() {
+        identity().step(0f64..=1f64, 1e-320);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many steps")]
+    fn step_rejects_infinite_ranges() {
+        identity().step(0f64..f64::INFINITY, 1f64);
+    }
+
+    #[test]
+    fn step_counts_points() {
+        let f = identity();
+        assert_eq!(f.step(0f64..=1f64, 0.1).len(), 11);
+        assert_eq!(f.step(0f64..1f64, 0.1).len(), 10);
+        assert_eq!(f.step(1f64..=0f64, 0.1).len(), 0);
+        assert_eq!(f.step(0f64..=1f64, 2f64.powi(-40)).len(), (1 << 40) + 1);
+    }
+}
@@ -1,27 +1,60 @@
 use console_engine::pixel;
 use console_engine::screen::Screen;
 use derivative::Derivative;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::iter::successors;
 use typed_builder::TypedBuilder;
 
+use crate::annotation::{draw_annotations, extremum_marker, Annotation, ROOT_MARKER};
+use crate::color::draw_screen;
 use crate::function::Function;
+use crate::parametric::Parametric;
+use crate::sampling::{ColumnRange, Sampling};
+use crate::theme::Theme;
 use crate::traits::AsF64;
-use crate::types::{Character, ColorWrapper, Scales};
+use crate::types::{Character, ColorWrapper, Fill, LineStyle, Scales};
 
 #[derive(Derivative, TypedBuilder, Debug)]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GraphOptions {
     #[builder(default)]
     pub color: ColorWrapper,
     #[builder(default)]
     pub character: Character,
+    /// Columns the function is drawn on, e.g. every other one with [`LineStyle::Dotted`].
+    #[builder(default)]
+    pub line_style: LineStyle,
+    /// Name of the function in the legend of [`MultiGraph`](crate::MultiGraph), shown below the chart if any function has one.
+    #[builder(default, setter(strip_option, into))]
+    pub label: Option<String>,
     #[derivative(Default(value = "true"))]
     #[builder(default = true)]
     pub height_legend: bool,
     #[derivative(Default)]
     #[builder(default, setter(into))]
     pub scales: Scales,
+    /// Paint the area between the function and the x axis.
+    #[builder(default)]
+    pub fill: Fill,
+    /// Overlay the derivative of the function, drawn with `∘` in its color, where it fits in the chart of the functions. Only supported in [`MultiGraph`](crate::MultiGraph).
+    #[builder(default)]
+    pub derivative: bool,
+    /// Overlay the integral of the function from `x = 0`, drawn with `×` in its color, where it fits in the chart of the functions. Only supported in [`MultiGraph`](crate::MultiGraph).
+    #[builder(default)]
+    pub integral: bool,
+    /// Mark the points where the function crosses zero (`○`) with their coordinates.
+    #[builder(default)]
+    pub roots: bool,
+    /// Mark the local maxima (`▲`) and minima (`▼`) of the function with their coordinates.
+    #[builder(default)]
+    pub extrema: bool,
+    /// How the function is evaluated. Only uniform sampling is supported in the stacked modes of [`MultiGraph`](crate::MultiGraph).
+    #[builder(default)]
+    pub sampling: Sampling,
 }
 
 pub struct Graph {
@@ -29,7 +62,11 @@ pub struct Graph {
     height: u32,
     graph_height: u32,
     pts: Vec<(f64, f64)>,
+    /// Smallest and largest value within each column of adaptively sampled functions, with its x coordinate.
+    ranges: Vec<ColumnRange>,
+    annotations: Vec<Annotation>,
     options: GraphOptions,
+    theme: Theme,
 }
 
 pub struct GraphWidths {
@@ -45,38 +82,127 @@ impl Graph {
         width: u32,
         set_height: Option<u32>,
     ) -> Graph {
-        Graph::with_options(f, width, set_height, GraphOptions::default())
+        Graph::with_options(
+            f,
+            width,
+            set_height,
+            GraphOptions::default(),
+            Theme::default(),
+        )
     }
 
     pub fn new_screen<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(f: Function<X, Y, F>) -> Graph {
-        Graph::with_options_screen(f, GraphOptions::default())
+        Graph::with_options_screen(f, GraphOptions::default(), Theme::default())
     }
 
+    /// `theme` gives the colors of the axis, legend, background and grid, like the `theme` of the options of other charts. The function is drawn as set in `options`.
     pub fn with_options<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
         f: Function<X, Y, F>,
         width: u32,
         set_height: Option<u32>,
         options: GraphOptions,
+        theme: Theme,
     ) -> Graph {
         // Generate function (x, y) pairs
-        let mut pts: Vec<(f64, f64)> = f
-            .rng_x_scale(0, width, &options.scales) // TODO Change this to have a Scales struct in options.scales
+        let (pts, ranges) = match options.sampling {
+            Sampling::Uniform => (
+                f.rng_x_scale(0, width, &options.scales), // TODO Change this to have a Scales struct in options.scales
+                Vec::new(),
+            ),
+            Sampling::Adaptive => split_ranges(f.rng_adaptive(0, width, &options.scales)),
+        };
+        let mut graph = Graph::from_pts(pts, ranges, width, set_height, options, theme);
+        graph.annotations = graph.annotate(&f);
+        graph
+    }
+
+    /// Same as [`Graph::with_options`], evaluating the function at the columns in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn with_options_parallel<X: AsF64 + Sync, Y: AsF64 + Sync, F: Fn(X) -> Y + Sync>(
+        f: Function<X, Y, F>,
+        width: u32,
+        set_height: Option<u32>,
+        options: GraphOptions,
+        theme: Theme,
+    ) -> Graph {
+        let (pts, ranges) = match options.sampling {
+            Sampling::Uniform => (f.par_rng_x_scale(0, width, &options.scales), Vec::new()),
+            Sampling::Adaptive => split_ranges(f.par_rng_adaptive(0, width, &options.scales)),
+        };
+        let mut graph = Graph::from_pts(pts, ranges, width, set_height, options, theme);
+        graph.annotations = graph.annotate(&f);
+        graph
+    }
+
+    /// Graph of a parametric curve, its x coordinates stretched by `p.aspect_ratio`. Points with negative coordinates are not drawn, so shift the curve if needed.
+    pub fn parametric<X: AsF64, Y: AsF64, F: Fn(f64) -> (X, Y)>(
+        p: Parametric<X, Y, F>,
+        width: u32,
+        set_height: Option<u32>,
+    ) -> Graph {
+        Graph::parametric_with_options(
+            p,
+            width,
+            set_height,
+            GraphOptions::default(),
+            Theme::default(),
+        )
+    }
+
+    pub fn parametric_screen<X: AsF64, Y: AsF64, F: Fn(f64) -> (X, Y)>(
+        p: Parametric<X, Y, F>,
+    ) -> Graph {
+        Graph::parametric_with_options_screen(p, GraphOptions::default(), Theme::default())
+    }
+
+    pub fn parametric_with_options<X: AsF64, Y: AsF64, F: Fn(f64) -> (X, Y)>(
+        p: Parametric<X, Y, F>,
+        width: u32,
+        set_height: Option<u32>,
+        options: GraphOptions,
+        theme: Theme,
+    ) -> Graph {
+        let pts: Vec<(f64, f64)> = p
+            .pts()
             .into_iter()
-            .map(|(x, y)| (x.as_f64(), y))
+            .map(|(x, y)| (x * p.aspect_ratio, y * options.scales.y))
+            .filter(|(x, y)| *x >= 0f64 && *y >= 0f64)
             .collect();
+        Graph::from_pts(pts, Vec::new(), width, set_height, options, theme)
+    }
+
+    pub fn parametric_with_options_screen<X: AsF64, Y: AsF64, F: Fn(f64) -> (X, Y)>(
+        p: Parametric<X, Y, F>,
+        options: GraphOptions,
+        theme: Theme,
+    ) -> Graph {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        Graph::parametric_with_options(p, w_screen as u32, None, options, theme)
+    }
+
+    /// `pts` hold the x coordinate (in columns times `scales.x`) and the already scaled y coordinate of each point.
+    fn from_pts(
+        mut pts: Vec<(f64, f64)>,
+        mut ranges: Vec<ColumnRange>,
+        width: u32,
+        set_height: Option<u32>,
+        options: GraphOptions,
+        theme: Theme,
+    ) -> Graph {
         // Get max y
         let max = pts
             .iter()
             .map(|(_, y)| *y)
+            .chain(ranges.iter().map(|r| r.max))
             .fold(f64::NEG_INFINITY, f64::max)
             .ceil() as u32;
         // Get digits of maximum number
         let max_height_digits =
-            successors(Some(max), |&n| (n >= 10).then(|| n / 10)).count() as u32;
+            successors(Some(max), |&n| (n >= 10).then_some(n / 10)).count() as u32;
         // Remove elements that shouldn't be printed because of legend
-        for _ in 0..max_height_digits {
-            pts.pop();
-        }
+        let graph_width = width - max_height_digits;
+        pts.retain(|(x, _)| x / options.scales.x <= graph_width as f64);
+        ranges.retain(|r| r.x / options.scales.x <= graph_width as f64);
         let height = match set_height {
             Some(h) => h,
             None => max + 1,
@@ -85,22 +211,60 @@ impl Graph {
             // f,
             widths: GraphWidths {
                 total: width,
-                graph: width - max_height_digits,
+                graph: graph_width,
                 height_legend: max_height_digits,
             },
             height,
             graph_height: height - 1,
             pts,
+            ranges,
+            annotations: Vec::new(),
             options,
+            theme,
+        }
+    }
+
+    /// Roots and extrema of `f` in the plotted range, as enabled in the options.
+    fn annotate<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+        &self,
+        f: &Function<X, Y, F>,
+    ) -> Vec<Annotation> {
+        let scales = &self.options.scales;
+        let end = self.widths.graph as f64 * scales.x;
+        let annotation = |(x, y): (f64, f64), marker: char| Annotation {
+            position: (
+                (x / scales.x).round() as i32 + self.widths.height_legend as i32,
+                (self.graph_height as f64 - y * scales.y).round() as i32,
+            ),
+            marker,
+            color: self.options.color,
+            point: (x, y),
+        };
+        let mut annotations = Vec::new();
+        if self.options.roots {
+            annotations.extend(
+                f.roots(0f64, end)
+                    .into_iter()
+                    .map(|x| annotation((x, 0f64), ROOT_MARKER)),
+            );
+        }
+        if self.options.extrema {
+            annotations.extend(
+                f.extrema(0f64, end)
+                    .into_iter()
+                    .map(|e| annotation((e.x, e.y), extremum_marker(e.kind))),
+            );
         }
+        annotations
     }
 
     pub fn with_options_screen<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
         f: Function<X, Y, F>,
         options: GraphOptions,
+        theme: Theme,
     ) -> Graph {
         let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
-        Graph::with_options(f, w_screen as u32, None, options)
+        Graph::with_options(f, w_screen as u32, None, options, theme)
     }
 
     pub fn draw(&self) {
@@ -110,9 +274,25 @@ impl Graph {
         if self.options.height_legend {
             self.draw_height_legend(&mut scr);
         }
+        self.theme.draw_grid(
+            &mut scr,
+            (self.widths.height_legend as i32, self.graph_height as i32),
+            self.widths.graph as i32,
+        );
+        self.draw_fill(&mut scr);
         self.draw_function(&mut scr);
+        draw_annotations(
+            &mut scr,
+            &self.annotations,
+            (
+                self.widths.height_legend as i32,
+                self.widths.total as i32 - 1,
+            ),
+            (0, self.graph_height as i32),
+        );
+        self.theme.paint_background(&mut scr);
 
-        scr.draw();
+        draw_screen(scr);
     }
 
     fn draw_axis(&self, scr: &mut Screen) {
@@ -121,13 +301,13 @@ impl Graph {
             (self.widths.height_legend + 1) as i32,
             self.graph_height as i32,
             self.widths.graph as i32,
-            pixel::pxl('_'),
+            pixel::pxl_fg('_', self.theme.axis.into()),
         );
         scr.v_line(
             self.widths.height_legend as i32,
             0,
             self.height as i32,
-            pixel::pxl('|'),
+            pixel::pxl_fg('|', self.theme.axis.into()),
         ); // looks like top boundary is not included
     }
 
@@ -139,15 +319,46 @@ impl Graph {
                     (self.graph_height as f64 - h / self.options.scales.y)
                         .max(0f64)
                         .round() as i32,
-                    pixel::pxl(digit),
+                    pixel::pxl_fg(digit, self.theme.legend.into()),
                 );
             }
         }
     }
 
+    fn draw_fill(&self, scr: &mut Screen) {
+        for (x, y) in self.pts.iter() {
+            let column = (x / self.options.scales.x) as i32 + self.widths.height_legend as i32;
+            let row = (self.graph_height as f64 - y).round() as i32;
+            // Paint from below the curve down to the row above the x axis
+            for r in row.saturating_add(1).max(0)..self.graph_height as i32 {
+                self.options.fill.paint(scr, column, r, self.options.color);
+            }
+        }
+    }
+
     fn draw_function(&self, scr: &mut Screen) {
+        let pxl = pixel::pxl_fg(self.options.character.as_char(), self.options.color.into());
+        // Whole range of values within each column
+        for r in self.ranges.iter().filter(|r| {
+            !r.min.is_nan()
+                && self
+                    .options
+                    .line_style
+                    .draws((r.x / self.options.scales.x) as u32)
+        }) {
+            let column = (r.x / self.options.scales.x) as i32 + self.widths.height_legend as i32;
+            let top = (self.graph_height as f64 - r.max).round() as i32;
+            let bottom = (self.graph_height as f64 - r.min).round() as i32;
+            for row in top.max(0)..=bottom.min(self.graph_height as i32) {
+                scr.set_pxl(column, row, pxl);
+            }
+        }
         // Draw points
-        for (x, y) in self.pts.iter() {
+        for (x, y) in self.pts.iter().filter(|(x, _)| {
+            self.options
+                .line_style
+                .draws((x / self.options.scales.x) as u32)
+        }) {
             scr.set_pxl(
                 (x / self.options.scales.x) as i32 + self.widths.height_legend as i32,
                 (self.graph_height as f64 - (y)).round() as i32, // TODO Allow selecting approximation method: round, ceil or cast (as)
@@ -158,9 +369,35 @@ impl Graph {
     }
 }
 
+/// Points at the value of each column, and the range of each column.
+fn split_ranges(ranges: Vec<ColumnRange>) -> (Vec<(f64, f64)>, Vec<ColumnRange>) {
+    (ranges.iter().map(|r| (r.x, r.y)).collect(), ranges)
+}
+
 impl fmt::Display for Graph {
     fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         self.draw();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    #[test]
+    fn parametric_drops_negative_points() {
+        // Circle of radius 4 around (2, 2), partly out of the first quadrant
+        let circle = Parametric::new(
+            |t: f64| (2f64 + 4f64 * t.cos(), 2f64 + 4f64 * t.sin()),
+            0f64,
+            TAU,
+            100,
+        );
+        let graph = Graph::parametric(circle, 40, Some(10));
+        assert!(!graph.pts.is_empty());
+        assert!(graph.pts.len() < 100);
+        assert!(graph.pts.iter().all(|(x, y)| *x >= 0f64 && *y >= 0f64));
+    }
+}
@@ -0,0 +1,342 @@
+use console_engine::pixel::{self, Pixel};
+use console_engine::screen::Screen;
+use console_engine::Color;
+use derivative::Derivative;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::bar_chart::format_value;
+use crate::color::{draw_screen, ColorSupport};
+use crate::function::Function2;
+use crate::plane::Plane;
+use crate::theme::Theme;
+use crate::traits::AsF64;
+use crate::types::{ColorWrapper, Domain};
+
+/// Shade characters from the lowest to the highest value.
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+/// Width of the color bar itself, without its labels.
+const COLOR_BAR_WIDTH: u32 = 2;
+
+/// How each cell of a [`Heatmap`] shows its value.
+#[derive(Copy, Clone, Derivative, Debug, PartialEq, Eq)]
+#[derivative(Default)]
+pub enum Shading {
+    #[derivative(Default)]
+    /// **(Default)** Background color picked from the gradient, shade characters when drawn without colors
+    Background,
+    /// Shade characters (`░▒▓█`) in the color picked from the gradient, readable without colors too
+    Characters,
+}
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct HeatmapOptions {
+    #[builder(default)]
+    pub shading: Shading,
+    /// Colors from the lowest to the highest value, values in between get the nearest one.
+    #[derivative(Default(value = "default_gradient()"))]
+    #[builder(default = default_gradient())]
+    pub colors: Vec<ColorWrapper>,
+    /// Range of values mapped to the colors, values outside of it are clamped. The minimum and maximum of the function if `None`.
+    #[builder(default, setter(strip_option))]
+    pub range: Option<(f64, f64)>,
+    /// Draw a color bar with the value of each color on the right.
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub color_bar: bool,
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub height_legend: bool,
+    /// Colors of the axis, legends, background and grid. The cells and the color bar use `colors`.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+pub(crate) fn default_gradient() -> Vec<ColorWrapper> {
+    [
+        Color::DarkBlue,
+        Color::Blue,
+        Color::Cyan,
+        Color::Green,
+        Color::Yellow,
+        Color::Red,
+        Color::DarkRed,
+    ]
+    .iter()
+    .map(|c| (*c).into())
+    .collect()
+}
+
+/// Heatmap of a function of two variables `z = f(x, y)` over a [`Domain`], each cell showing the value at its center.
+pub struct Heatmap {
+    plane: Plane,
+    values: Vec<Vec<f64>>,
+    range: (f64, f64),
+    width: u32,
+    options: HeatmapOptions,
+}
+
+impl Heatmap {
+    /// `width` includes the color bar. `set_height` is the height of the plotting area, by default the one keeping the same scale on both axes.
+    pub fn new<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+    ) -> Heatmap {
+        Heatmap::with_options(f, domain, width, set_height, HeatmapOptions::default())
+    }
+
+    pub fn new_screen<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+    ) -> Heatmap {
+        Heatmap::with_options_screen(f, domain, HeatmapOptions::default())
+    }
+
+    pub fn with_options<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+        options: HeatmapOptions,
+    ) -> Heatmap {
+        let (plane, values, range) = if options.color_bar {
+            // The width of the color bar depends on the range of values, unless it is given take it from the values over the whole width
+            let range = options.range.unwrap_or_else(|| {
+                value_range(&sample(&Plane::new(domain, width, set_height), &f))
+            });
+            let color_bar = 1
+                + COLOR_BAR_WIDTH
+                + 1
+                + color_bar_labels(range)
+                    .iter()
+                    .map(|l| l.len())
+                    .max()
+                    .unwrap_or_default() as u32;
+            let plane = Plane::new(domain, width.saturating_sub(color_bar), set_height);
+            let values = sample(&plane, &f);
+            (plane, values, range)
+        } else {
+            let plane = Plane::new(domain, width, set_height);
+            let values = sample(&plane, &f);
+            let range = options.range.unwrap_or_else(|| value_range(&values));
+            (plane, values, range)
+        };
+        Heatmap {
+            plane,
+            values,
+            range,
+            width,
+            options,
+        }
+    }
+
+    pub fn with_options_screen<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        options: HeatmapOptions,
+    ) -> Heatmap {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        Heatmap::with_options(f, domain, w_screen as u32, None, options)
+    }
+
+    pub fn draw(&self) {
+        draw_screen(self.render());
+    }
+
+    pub(crate) fn render(&self) -> Screen {
+        let mut scr = Screen::new(self.width, self.plane.height);
+
+        self.plane.draw_axis(&mut scr, &self.options.theme);
+        if self.options.height_legend {
+            self.plane.draw_legends(&mut scr, &self.options.theme);
+        }
+        self.draw_cells(&mut scr);
+        if self.options.color_bar {
+            self.draw_color_bar(&mut scr);
+        }
+
+        self.options.theme.paint_background(&mut scr);
+
+        scr
+    }
+
+    /// Pixel showing value `z`, `None` for values that couldn't be computed.
+    fn pixel(&self, z: f64) -> Option<Pixel> {
+        if !z.is_finite() || self.options.colors.is_empty() {
+            return None;
+        }
+        let (min, max) = self.range;
+        let t = if max > min {
+            ((z - min) / (max - min)).clamp(0f64, 1f64)
+        } else {
+            0f64
+        };
+        let color: Color = self.options.colors
+            [(t * (self.options.colors.len() - 1) as f64).round() as usize]
+            .into();
+        // Without colors backgrounds can't tell values apart
+        let shading = match ColorSupport::current() {
+            ColorSupport::None => Shading::Characters,
+            _ => self.options.shading,
+        };
+        Some(match shading {
+            Shading::Background => pixel::pxl_bg(' ', color),
+            Shading::Characters => pixel::pxl_fg(
+                SHADES[(t * (SHADES.len() - 1) as f64).round() as usize],
+                color,
+            ),
+        })
+    }
+
+    fn draw_cells(&self, scr: &mut Screen) {
+        for (row, values) in self.values.iter().enumerate() {
+            for (col, z) in values.iter().enumerate() {
+                if let Some(pxl) = self.pixel(*z) {
+                    scr.set_pxl(
+                        col as i32 + self.plane.widths.height_legend as i32 + 1,
+                        row as i32,
+                        pxl,
+                    );
+                }
+            }
+        }
+    }
+
+    fn draw_color_bar(&self, scr: &mut Screen) {
+        let x = self.plane.widths.total as i32 + 1;
+        let rows = self.plane.rows();
+        let (min, max) = self.range;
+        for row in 0..rows {
+            let z = if rows > 1 {
+                max - row as f64 / (rows - 1) as f64 * (max - min)
+            } else {
+                max
+            };
+            if let Some(pxl) = self.pixel(z) {
+                for i in 0..COLOR_BAR_WIDTH as i32 {
+                    scr.set_pxl(x + i, row as i32, pxl);
+                }
+            }
+        }
+        // Maximum at the top, middle value and minimum at the bottom
+        let labels = color_bar_labels(self.range);
+        let label_x = x + COLOR_BAR_WIDTH as i32 + 1;
+        let theme = &self.options.theme;
+        theme.print_legend(scr, label_x, 0, &labels[0]);
+        if rows > 2 {
+            theme.print_legend(scr, label_x, (rows / 2) as i32, &labels[1]);
+        }
+        theme.print_legend(scr, label_x, rows as i32 - 1, &labels[2]);
+    }
+}
+
+impl fmt::Display for Heatmap {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+/// Values of `f` at the center of every cell of the plotting area, row by row.
+fn sample<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+    plane: &Plane,
+    f: &Function2<X, Y, Z, F>,
+) -> Vec<Vec<f64>> {
+    (0..plane.rows())
+        .map(|row| {
+            (0..plane.cols())
+                .map(|col| {
+                    let (x, y) = plane.center(col, row);
+                    f.at(x, y)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn value_range(values: &[Vec<f64>]) -> (f64, f64) {
+    values
+        .iter()
+        .flatten()
+        .filter(|z| z.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), z| {
+            (min.min(*z), max.max(*z))
+        })
+}
+
+/// Labels of the color bar: maximum, middle value and minimum.
+fn color_bar_labels((min, max): (f64, f64)) -> [String; 3] {
+    [
+        format_value(max),
+        format_value((min + max) / 2f64),
+        format_value(min),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::plain_text;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Evaluations of the function to build a heatmap with `options`, and cells of the heatmap.
+    fn evaluations(options: HeatmapOptions) -> (u32, u32) {
+        let count = AtomicU32::new(0);
+        let f = Function2::new(|x: f64, y: f64| {
+            count.fetch_add(1, Ordering::Relaxed);
+            x + y
+        });
+        let domain = ((-1f64, 1f64), (-1f64, 1f64)).into();
+        let heatmap = Heatmap::with_options(f, domain, 40, Some(10), options);
+        (
+            count.into_inner(),
+            heatmap.plane.rows() * heatmap.plane.cols(),
+        )
+    }
+
+    #[test]
+    fn samples_once_without_estimate() {
+        let (count, cells) = evaluations(HeatmapOptions::builder().range((0f64, 1f64)).build());
+        assert_eq!(count, cells);
+        let (count, cells) = evaluations(HeatmapOptions::builder().color_bar(false).build());
+        assert_eq!(count, cells);
+    }
+
+    #[test]
+    fn cells_are_shaded_without_colors() {
+        // Tests don't write to a terminal, so they draw without colors
+        let heatmap = Heatmap::new(
+            Function2::new(|x: f64, y: f64| x + y),
+            ((-1f64, 1f64), (-1f64, 1f64)).into(),
+            40,
+            Some(10),
+        );
+        let text = plain_text(&heatmap.render());
+        let first_cell = heatmap.plane.widths.height_legend as usize + 1;
+        for row in text.lines().take(10) {
+            let cells: String = row.chars().skip(first_cell).take(10).collect();
+            assert!(cells.chars().all(|c| SHADES.contains(&c)), "{row}");
+        }
+    }
+
+    #[test]
+    fn color_bar_uses_the_estimated_range() {
+        let f = || Function2::new(|x: f64, y: f64| x * y);
+        let domain: Domain = ((-1f64, 1f64), (-1f64, 1f64)).into();
+        let heatmap = Heatmap::with_options(f(), domain, 40, Some(10), HeatmapOptions::default());
+        // Estimated over the whole width, before making room for the color bar
+        let estimate = value_range(&sample(&Plane::new(domain, 40, Some(10)), &f()));
+        assert_eq!(heatmap.range, estimate);
+        let (min, max) = heatmap.range;
+        assert!(min < 0f64 && max > 0f64);
+        let text = plain_text(&heatmap.render());
+        let rows: Vec<&str> = text.lines().map(str::trim_end).collect();
+        let labels = color_bar_labels(estimate);
+        assert!(rows[0].ends_with(&format!(" {}", labels[0])));
+        assert!(rows[5].ends_with(&format!(" {}", labels[1])));
+        assert!(rows[9].ends_with(&format!(" {}", labels[2])));
+    }
+}
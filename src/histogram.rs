@@ -0,0 +1,339 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use console_engine::Color;
+use derivative::Derivative;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::bar_chart::{format_value, BarChart, BarChartOptions};
+use crate::color::draw_screen;
+use crate::function::Function;
+use crate::traits::{AsF64, MaybeAsF64};
+use crate::types::{Character, ColorWrapper};
+
+/// Most bins of a histogram, one row each, whatever the [`Binning`].
+const MAX_BINS: u32 = 100;
+
+/// Strategy used to split samples into the bins of a [`Histogram`]. Every strategy makes at most 100 bins.
+#[derive(Copy, Clone, Derivative, Debug)]
+#[derivative(Default)]
+pub enum Binning {
+    #[derivative(Default)]
+    /// **(Default)** Sturges' rule, `log2(n) + 1` bins. Good for small, roughly normal samples
+    Sturges,
+    /// Freedman–Diaconis rule, bin width of `2 * IQR / n^(1/3)`, with at most one bin per sample. Its width isn't skewed by outliers. Falls back to Sturges' rule if the IQR is 0
+    FreedmanDiaconis,
+    /// Fixed number of bins
+    Count(u32),
+    /// Fixed bin width, widened if there would be more bins than samples or than the maximum
+    Width(f64),
+}
+
+/// A single bin of a histogram, holding the samples in `[start, end)` (the last bin also includes `end`).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bin {
+    pub start: f64,
+    pub end: f64,
+    pub count: u32,
+}
+
+impl Binning {
+    /// Splits `samples` into bins spanning from the minimum to the maximum sample. Non-finite samples are ignored.
+    ///
+    /// ```
+    /// use tgraph::Binning;
+    ///
+    /// let bins = Binning::Count(2).bin(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// assert_eq!(bins.len(), 2);
+    /// assert_eq!((bins[0].start, bins[0].end), (1.0, 3.0));
+    /// assert_eq!((bins[0].count, bins[1].count), (2, 3));
+    /// ```
+    pub fn bin(&self, samples: &[f64]) -> Vec<Bin> {
+        let mut sorted: Vec<f64> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+        if sorted.is_empty() {
+            return Vec::new();
+        }
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+        let range = max - min;
+        if range == 0f64 {
+            return vec![Bin {
+                start: min,
+                end: max,
+                count: sorted.len() as u32,
+            }];
+        }
+
+        let count = match *self {
+            Binning::Sturges => sturges(sorted.len()),
+            Binning::FreedmanDiaconis => {
+                let iqr = percentile(&sorted, 0.75) - percentile(&sorted, 0.25);
+                let width = 2f64 * iqr / (sorted.len() as f64).cbrt();
+                if width > 0f64 {
+                    bins_of_width(range, width, sorted.len())
+                } else {
+                    sturges(sorted.len())
+                }
+            }
+            Binning::Count(n) => n,
+            Binning::Width(width) if width > 0f64 => bins_of_width(range, width, sorted.len()),
+            Binning::Width(_) => 1,
+        }
+        .clamp(1, MAX_BINS);
+        let width = match *self {
+            Binning::Width(width) if width > 0f64 && range / width <= count as f64 => width,
+            _ => range / count as f64,
+        };
+
+        let mut bins: Vec<Bin> = (0..count)
+            .map(|i| Bin {
+                start: min + i as f64 * width,
+                end: min + (i + 1) as f64 * width,
+                count: 0,
+            })
+            .collect();
+        for v in sorted {
+            let i = (((v - min) / width).floor() as usize).min(bins.len() - 1);
+            bins[i].count += 1;
+        }
+        bins
+    }
+}
+
+/// Bins of `width` needed to cover `range`, at most one per sample so that tiny widths can't make huge histograms.
+fn bins_of_width(range: f64, width: f64, samples: usize) -> u32 {
+    (range / width).ceil().min(samples as f64) as u32
+}
+
+fn sturges(n: usize) -> u32 {
+    (n as f64).log2().ceil() as u32 + 1
+}
+
+/// Linearly interpolated percentile `p` (0 to 1) of already sorted values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct HistogramOptions {
+    #[builder(default)]
+    pub binning: Binning,
+    #[builder(default)]
+    pub bars: BarChartOptions,
+    /// Character used to draw the density curve, if one is set with [`Histogram::density`].
+    #[builder(default)]
+    pub density_character: Character,
+    #[derivative(Default(value = "Color::Red.into()"))]
+    #[builder(default = Color::Red.into())]
+    pub density_color: ColorWrapper,
+}
+
+/// Histogram of raw samples, drawn as a horizontal [`BarChart`] with one bar per bin.
+pub struct Histogram {
+    bins: Vec<Bin>,
+    samples: u32,
+    chart: BarChart,
+    density: Vec<f64>,
+    options: HistogramOptions,
+}
+
+impl Histogram {
+    /// `samples` can be any iterator of numbers (`&[f64]`, `Vec<u32>`...), missing values (`None`) are skipped. `width` refers to the total width of the chart, including the bin labels and the count legend.
+    pub fn new<I: IntoIterator<Item = V>, V: MaybeAsF64>(samples: I, width: u32) -> Histogram {
+        Histogram::with_options(samples, width, HistogramOptions::default())
+    }
+
+    pub fn new_screen<I: IntoIterator<Item = V>, V: MaybeAsF64>(samples: I) -> Histogram {
+        Histogram::with_options_screen(samples, HistogramOptions::default())
+    }
+
+    pub fn with_options<I: IntoIterator<Item = V>, V: MaybeAsF64>(
+        samples: I,
+        width: u32,
+        options: HistogramOptions,
+    ) -> Histogram {
+        let samples: Vec<f64> = samples
+            .into_iter()
+            .filter_map(|v| v.maybe_as_f64())
+            .collect();
+        let bins = options.binning.bin(&samples);
+        let last = bins.len().saturating_sub(1);
+        let chart = BarChart::with_options(
+            bins.iter()
+                .enumerate()
+                .map(|(i, bin)| {
+                    let close = if i == last { ']' } else { ')' };
+                    (
+                        format!(
+                            "[{}, {}{}",
+                            format_value(bin.start),
+                            format_value(bin.end),
+                            close
+                        ),
+                        bin.count,
+                    )
+                })
+                .collect(),
+            width,
+            options.bars.clone(),
+        );
+        Histogram {
+            samples: bins.iter().map(|bin| bin.count).sum(),
+            bins,
+            chart,
+            density: Vec::new(),
+            options,
+        }
+    }
+
+    pub fn with_options_screen<I: IntoIterator<Item = V>, V: MaybeAsF64>(
+        samples: I,
+        options: HistogramOptions,
+    ) -> Histogram {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        Histogram::with_options(samples, w_screen as u32, options)
+    }
+
+    /// Overlays a probability density function on top of the bars, drawn as the count expected in each bin (`samples * bin width * f(bin center)`).
+    pub fn density<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(mut self, f: Function<X, Y, F>) -> Histogram {
+        self.density = self
+            .bins
+            .iter()
+            .map(|bin| {
+                let width = bin.end - bin.start;
+                self.samples as f64 * width * f.at(bin.start + width / 2f64)
+            })
+            .collect();
+        self.chart
+            .fit_max(self.density.iter().copied().fold(0f64, f64::max));
+        self
+    }
+
+    pub fn bins(&self) -> &[Bin] {
+        &self.bins
+    }
+
+    pub fn draw(&self) {
+        let mut scr = self.chart.render();
+
+        self.draw_density(&mut scr);
+        self.chart.options.theme.paint_background(&mut scr);
+
+        draw_screen(scr);
+    }
+
+    fn draw_density(&self, scr: &mut Screen) {
+        for (row, expected) in self.density.iter().enumerate() {
+            let x = self.chart.column_of(*expected);
+            // Don't hide the count legend
+            if matches!(scr.get_pxl(x, row as i32), Ok(p) if p.chr.is_ascii_graphic()) {
+                continue;
+            }
+            scr.set_pxl(
+                x,
+                row as i32,
+                pixel::pxl_fg(
+                    self.options.density_character.as_char(),
+                    self.options.density_color.into(),
+                ),
+            );
+        }
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(bins: &[Bin]) -> Vec<u32> {
+        bins.iter().map(|b| b.count).collect()
+    }
+
+    #[test]
+    fn sturges_bins() {
+        let samples: Vec<f64> = (0..16).map(f64::from).collect();
+        let bins = Binning::Sturges.bin(&samples);
+        assert_eq!(bins.len(), 5);
+        assert_eq!(bins.iter().map(|b| b.count).sum::<u32>(), 16);
+        assert_eq!((bins[0].start, bins[4].end), (0f64, 15f64));
+    }
+
+    #[test]
+    fn maximum_goes_in_the_last_bin() {
+        let bins = Binning::Count(3).bin(&[0f64, 1f64, 2f64, 3f64]);
+        assert_eq!(counts(&bins), vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn non_finite_samples_are_ignored() {
+        let bins = Binning::Count(2).bin(&[f64::NAN, 0f64, f64::INFINITY, 2f64]);
+        assert_eq!(counts(&bins), vec![1, 1]);
+        assert!(Binning::Sturges.bin(&[f64::NAN]).is_empty());
+    }
+
+    #[test]
+    fn equal_samples_make_one_bin() {
+        let bins = Binning::FreedmanDiaconis.bin(&[4f64; 10]);
+        assert_eq!(
+            bins,
+            vec![Bin {
+                start: 4f64,
+                end: 4f64,
+                count: 10
+            }]
+        );
+    }
+
+    #[test]
+    fn fixed_width() {
+        let bins = Binning::Width(2f64).bin(&[0f64, 1f64, 2f64, 3f64, 4f64, 5f64]);
+        assert_eq!(counts(&bins), vec![2, 2, 2]);
+        assert_eq!(bins[1].start, 2f64);
+        // Widened to one bin per sample
+        let bins = Binning::Width(1e-300).bin(&[0f64, 1f64, 2f64]);
+        assert_eq!(bins.len(), 3);
+        assert_eq!(Binning::Width(-1f64).bin(&[0f64, 1f64]).len(), 1);
+    }
+
+    #[test]
+    fn freedman_diaconis_with_outlier() {
+        let mut samples: Vec<f64> = (0..1000).map(|i| 1e-6 + i as f64 * 1e-12).collect();
+        samples.push(1e6);
+        let bins = Binning::FreedmanDiaconis.bin(&samples);
+        assert_eq!(bins.len() as u32, MAX_BINS);
+        assert_eq!(bins.iter().map(|b| b.count).sum::<u32>(), 1001);
+    }
+
+    #[test]
+    fn freedman_diaconis_without_spread_falls_back_to_sturges() {
+        let mut samples = vec![1f64; 20];
+        samples.push(5f64);
+        assert_eq!(
+            Binning::FreedmanDiaconis.bin(&samples).len(),
+            Binning::Sturges.bin(&samples).len()
+        );
+    }
+
+    #[test]
+    fn bins_are_capped() {
+        let samples: Vec<f64> = (0..1000).map(f64::from).collect();
+        assert_eq!(
+            Binning::Count(u32::MAX).bin(&samples).len() as u32,
+            MAX_BINS
+        );
+        let bins = Binning::Width(1f64).bin(&samples);
+        assert_eq!(bins.len() as u32, MAX_BINS);
+        assert_eq!(counts(&bins).iter().sum::<u32>(), 1000);
+        assert_eq!(Binning::Count(0).bin(&samples).len(), 1);
+    }
+}
@@ -0,0 +1,285 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use derivative::Derivative;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::color::draw_screen;
+use crate::function::Function2;
+use crate::graph::GraphOptions;
+use crate::plane::Plane;
+use crate::theme::Theme;
+use crate::traits::AsF64;
+use crate::types::Domain;
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct ImplicitOptions {
+    /// Color and character of the curve.
+    #[builder(default)]
+    pub graph: GraphOptions,
+    /// Number of samples per cell side, higher values find thinner features of the curve.
+    #[derivative(Default(value = "4"))]
+    #[builder(default = 4)]
+    pub resolution: u32,
+    /// Colors of the axis, legends, background and grid.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+/// Graph of the implicit curve `f(x, y) = 0` over a [`Domain`], traced with marching squares on a grid finer than the terminal cells.
+pub struct ImplicitGraph {
+    plane: Plane,
+    cells: Vec<(i32, i32)>,
+    options: ImplicitOptions,
+}
+
+impl ImplicitGraph {
+    /// `set_height` is the height of the plotting area, by default the one keeping the same scale on both axes.
+    pub fn new<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+    ) -> ImplicitGraph {
+        ImplicitGraph::with_options(f, domain, width, set_height, ImplicitOptions::default())
+    }
+
+    pub fn new_screen<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+    ) -> ImplicitGraph {
+        ImplicitGraph::with_options_screen(f, domain, ImplicitOptions::default())
+    }
+
+    pub fn with_options<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+        options: ImplicitOptions,
+    ) -> ImplicitGraph {
+        let plane = Plane::new(domain, width, set_height);
+        let resolution = options.resolution.max(1);
+        let values = sample_grid(&plane, resolution, |x, y| f.at(x, y));
+        let cells = marching_squares(&plane, resolution, &values, 0f64);
+        ImplicitGraph {
+            plane,
+            cells,
+            options,
+        }
+    }
+
+    pub fn with_options_screen<X: AsF64, Y: AsF64, Z: AsF64, F: Fn(X, Y) -> Z>(
+        f: Function2<X, Y, Z, F>,
+        domain: Domain,
+        options: ImplicitOptions,
+    ) -> ImplicitGraph {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        ImplicitGraph::with_options(f, domain, w_screen as u32, None, options)
+    }
+
+    pub fn draw(&self) {
+        let mut scr = Screen::new(self.plane.widths.total, self.plane.height);
+
+        self.plane.draw_axis(&mut scr, &self.options.theme);
+        if self.options.graph.height_legend {
+            self.plane.draw_legends(&mut scr, &self.options.theme);
+        }
+        self.draw_curve(&mut scr);
+
+        self.options.theme.paint_background(&mut scr);
+
+        draw_screen(scr);
+    }
+
+    fn draw_curve(&self, scr: &mut Screen) {
+        for (x, y) in self.cells.iter() {
+            scr.set_pxl(
+                *x,
+                *y,
+                pixel::pxl_fg(
+                    self.options.graph.character.as_char(),
+                    self.options.graph.color.into(),
+                ),
+            );
+        }
+    }
+}
+
+impl fmt::Display for ImplicitGraph {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+/// Values of `f` on a grid of `resolution` samples per cell side covering the plotting area, row by row.
+pub(crate) fn sample_grid<F: Fn(f64, f64) -> f64>(
+    plane: &Plane,
+    resolution: u32,
+    f: F,
+) -> Vec<Vec<f64>> {
+    let step = 1f64 / resolution as f64;
+    (0..=plane.rows() * resolution)
+        .map(|j| {
+            (0..=plane.cols() * resolution)
+                .map(|i| {
+                    let (x, y) = plane.to_plane(i as f64 * step, j as f64 * step);
+                    f(x, y)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Screen cells crossed by the iso-line `f(x, y) = level`, `values` being the samples of `f` from [`sample_grid`].
+pub(crate) fn marching_squares(
+    plane: &Plane,
+    resolution: u32,
+    values: &[Vec<f64>],
+    level: f64,
+) -> Vec<(i32, i32)> {
+    let (nu, nv) = (
+        (plane.cols() * resolution) as usize,
+        (plane.rows() * resolution) as usize,
+    );
+    let step = 1f64 / resolution as f64;
+    let mut cells = Vec::new();
+    for j in 0..nv {
+        for i in 0..nu {
+            let (u, v) = (i as f64 * step, j as f64 * step);
+            // Corners clockwise from the top left one
+            let corners = [
+                values[j][i] - level,
+                values[j][i + 1] - level,
+                values[j + 1][i + 1] - level,
+                values[j + 1][i] - level,
+            ];
+            if corners.iter().any(|c| !c.is_finite()) {
+                continue;
+            }
+            let positions = [(u, v), (u + step, v), (u + step, v + step), (u, v + step)];
+            for (a, b) in cell_segments(corners, positions) {
+                for t in [0f64, 0.5, 1f64] {
+                    if let Some(cell) =
+                        plane.screen_at(a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+                    {
+                        cells.push(cell);
+                    }
+                }
+            }
+        }
+    }
+    cells.sort_unstable();
+    cells.dedup();
+    cells
+}
+
+/// Segments of the iso-line across a grid cell, `corners` being the values minus the level at the `positions` of the corners, clockwise from the top left one.
+fn cell_segments(corners: [f64; 4], positions: [(f64, f64); 4]) -> Vec<((f64, f64), (f64, f64))> {
+    // Crossing on each edge (top, right, bottom, left), interpolated between its corners
+    let crossings: Vec<Option<(f64, f64)>> = (0..4)
+        .map(|e| {
+            let (a, b) = (corners[e], corners[(e + 1) % 4]);
+            if (a > 0f64) == (b > 0f64) {
+                return None;
+            }
+            let t = a / (a - b);
+            let (pa, pb) = (positions[e], positions[(e + 1) % 4]);
+            Some((pa.0 + (pb.0 - pa.0) * t, pa.1 + (pb.1 - pa.1) * t))
+        })
+        .collect();
+    match crossings.iter().flatten().count() {
+        2 => {
+            let pts: Vec<(f64, f64)> = crossings.iter().flatten().copied().collect();
+            vec![(pts[0], pts[1])]
+        }
+        4 => {
+            let edge = |e: usize| crossings[e].unwrap();
+            // Saddle, the center decides which corners are connected
+            let center = corners.iter().sum::<f64>() / 4f64;
+            if (center > 0f64) == (corners[0] > 0f64) {
+                vec![(edge(0), edge(1)), (edge(2), edge(3))]
+            } else {
+                vec![(edge(3), edge(0)), (edge(1), edge(2))]
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT_SQUARE: [(f64, f64); 4] = [(0f64, 0f64), (1f64, 0f64), (1f64, 1f64), (0f64, 1f64)];
+
+    fn assert_segments(
+        segments: Vec<((f64, f64), (f64, f64))>,
+        expected: [((f64, f64), (f64, f64)); 2],
+    ) {
+        assert_eq!(segments.len(), 2);
+        for ((a, b), (ea, eb)) in segments.into_iter().zip(expected) {
+            for (p, e) in [(a, ea), (b, eb)] {
+                assert!(
+                    (p.0 - e.0).abs() < 1e-9 && (p.1 - e.1).abs() < 1e-9,
+                    "{p:?} != {e:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn circle_cells_are_on_the_radius() {
+        let plane = Plane::new(
+            Domain {
+                x: (-2f64, 2f64),
+                y: (-2f64, 2f64),
+            },
+            41,
+            Some(20),
+        );
+        let values = sample_grid(&plane, 4, |x, y| x * x + y * y - 1f64);
+        let cells = marching_squares(&plane, 4, &values, 0f64);
+        assert!(cells.len() > 20);
+        let cell = (
+            plane.domain.width() / plane.cols() as f64,
+            plane.domain.height() / plane.rows() as f64,
+        );
+        for (col, row) in cells {
+            let (x, y) = plane.center(
+                (col - plane.widths.height_legend as i32 - 1) as u32,
+                row as u32,
+            );
+            assert!(
+                ((x * x + y * y).sqrt() - 1f64).abs() <= cell.0.hypot(cell.1),
+                "({x}, {y})"
+            );
+        }
+    }
+
+    #[test]
+    fn one_crossing_pair_makes_one_segment() {
+        let segments = cell_segments([1f64, -1f64, -1f64, 1f64], UNIT_SQUARE);
+        assert_eq!(segments, vec![((0.5, 0f64), (0.5, 1f64))]);
+        assert!(cell_segments([1f64; 4], UNIT_SQUARE).is_empty());
+    }
+
+    #[test]
+    fn saddles_follow_the_center() {
+        // Negative center, the positive corners are cut off
+        assert_segments(
+            cell_segments([1f64, -1f64, 1f64, -1f64], UNIT_SQUARE),
+            [((0f64, 0.5), (0.5, 0f64)), ((1f64, 0.5), (0.5, 1f64))],
+        );
+        // Positive center, the negative corners are cut off
+        assert_segments(
+            cell_segments([2f64, -1f64, 2f64, -1f64], UNIT_SQUARE),
+            [
+                ((2f64 / 3f64, 0f64), (1f64, 1f64 / 3f64)),
+                ((1f64 / 3f64, 1f64), (0f64, 2f64 / 3f64)),
+            ],
+        );
+    }
+}
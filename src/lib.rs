@@ -1,18 +1,55 @@
+// `is_multiple_of` and `is_none_or` are newer than the versions of Rust the crate builds with
+#![allow(clippy::manual_is_multiple_of, clippy::unnecessary_map_or)]
+
 use console_engine::pixel;
 use console_engine::screen::Screen;
 use std::iter::successors;
 
+mod analysis;
+mod annotation;
+mod bar_chart;
+mod color;
+mod contour;
+mod expression;
 mod function;
 mod graph;
+mod heatmap;
+mod histogram;
+mod implicit;
 mod multi_graph;
+mod parametric;
+mod plane;
+mod polar;
+mod sampling;
+mod sparkline;
+mod streaming;
+mod theme;
+mod time_series;
 mod traits;
 mod types;
+mod vector_field;
 
+pub use crate::analysis::*;
+pub use crate::bar_chart::*;
+pub use crate::color::*;
+pub use crate::contour::*;
+pub use crate::expression::*;
 pub use crate::function::*;
 pub use crate::graph::*;
+pub use crate::heatmap::*;
+pub use crate::histogram::*;
+pub use crate::implicit::*;
 pub use crate::multi_graph::*;
+pub use crate::parametric::*;
+pub use crate::polar::*;
+pub use crate::sampling::*;
+pub use crate::sparkline::*;
+pub use crate::streaming::*;
+pub use crate::theme::*;
+pub use crate::time_series::*;
 pub use crate::traits::*;
 pub use crate::types::*;
+pub use crate::vector_field::*;
 
 /// Reexported from `console_engine::Color`.
 pub use console_engine::Color;
@@ -24,7 +61,7 @@ fn draw<F: Fn(u32) -> u32>(f: F, _character: Character) {
     let width = 80;
 
     // Generate values
-    let y: Vec<u32> = (0..=width).map(|x| f(x)).collect();
+    let y: Vec<u32> = (0..=width).map(f).collect();
 
     // Get maximum and minimum value
     let max = *y.iter().max().unwrap_or(&0);
@@ -33,7 +70,8 @@ fn draw<F: Fn(u32) -> u32>(f: F, _character: Character) {
     // Set graph height
     let height = max + 1;
 
-    let max_height_digits = successors(Some(height), |&n| (n >= 10).then(|| n / 10)).count() as u32;
+    let max_height_digits =
+        successors(Some(height), |&n| (n >= 10).then_some(n / 10)).count() as u32;
 
     // println!("H: {}; W: {}, y {:?}", height, width, y);
 
@@ -1,47 +1,224 @@
 use console_engine::pixel;
 use console_engine::screen::Screen;
 use console_engine::Color;
+use derivative::Derivative;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::iter::successors;
+use std::marker::PhantomData;
+use typed_builder::TypedBuilder;
 
-use crate::function::Function;
+use crate::annotation::{
+    draw_annotations, extremum_marker, Annotation, INTERSECTION_MARKER, ROOT_MARKER,
+};
+use crate::color::{draw_screen, ColorSupport};
+use crate::function::{derivative_at, integrate, Function, Integration};
 use crate::graph::{GraphOptions, GraphWidths};
+use crate::sampling::{ColumnRange, Sampling};
+use crate::theme::Theme;
 use crate::traits::AsF64;
+use crate::types::{Character, ColorWrapper, Fill, LineStyle, Scales, LINE_SAMPLE_WIDTH};
+
+/// Subintervals of each column when integrating a function for the integral overlay.
+const COLUMN_INTEGRAL_STEPS: u32 = 8;
+/// Characters given to the functions left with the default one, in order, when their colors can't tell them apart.
+const MARKERS: [Character; 7] = [
+    Character::Star,
+    Character::Bullet,
+    Character::CrossCircle,
+    Character::SmallTriangle,
+    Character::CrossPoint,
+    Character::Custom('+'),
+    Character::Custom('o'),
+];
+/// Line styles given to the functions left with the default one, in order, when their colors can't tell them apart.
+const LINE_STYLES: [LineStyle; 3] = [LineStyle::Solid, LineStyle::Dashed, LineStyle::Dotted];
+/// Blank columns between the entries of the legend.
+const LEGEND_GAP: usize = 3;
 
 pub struct MultiGraph<X: AsF64, Y: AsF64, F: Fn(X) -> Y> {
-    functions: Vec<Function<X, Y, F>>,
+    /// Each function is evaluated only once, when the graph is created.
+    samples: Vec<Samples>,
+    annotations: Vec<Annotation>,
     widths: GraphWidths,
     height: u32,
     graph_height: u32,
     options: MultiGraphOptions,
+    _functions: PhantomData<Function<X, Y, F>>,
+}
+
+/// Values of a function drawn in a [`MultiGraph`], from column 0 to the total width.
+struct Samples {
+    values: Vec<(u32, f64)>,
+    /// Range of each column if the function is sampled adaptively, empty otherwise.
+    ranges: Vec<ColumnRange>,
+    overlays: Vec<(Character, Vec<(u32, f64)>)>,
+}
+
+impl Samples {
+    fn new<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+        f: &Function<X, Y, F>,
+        options: &GraphOptions,
+        width: u32,
+    ) -> Samples {
+        match options.sampling {
+            Sampling::Uniform => Samples {
+                values: f.rng_x(0, width),
+                ranges: Vec::new(),
+                overlays: overlays(f, options, width),
+            },
+            Sampling::Adaptive => Samples::from_ranges(
+                f.rng_adaptive(0, width, &Scales::default()),
+                overlays(f, options, width),
+            ),
+        }
+    }
+
+    /// Same as [`Samples::new`], evaluating the columns in parallel.
+    #[cfg(feature = "rayon")]
+    fn new_parallel<X: AsF64 + Sync, Y: AsF64 + Sync, F: Fn(X) -> Y + Sync>(
+        f: &Function<X, Y, F>,
+        options: &GraphOptions,
+        width: u32,
+    ) -> Samples {
+        match options.sampling {
+            Sampling::Uniform => Samples {
+                values: f.par_rng_x(0, width),
+                ranges: Vec::new(),
+                overlays: overlays(f, options, width),
+            },
+            Sampling::Adaptive => Samples::from_ranges(
+                f.par_rng_adaptive(0, width, &Scales::default()),
+                overlays(f, options, width),
+            ),
+        }
+    }
+
+    /// Samples of an adaptively sampled function, whose values are the ones at the columns of its ranges.
+    fn from_ranges(
+        ranges: Vec<ColumnRange>,
+        overlays: Vec<(Character, Vec<(u32, f64)>)>,
+    ) -> Samples {
+        Samples {
+            values: ranges.iter().map(|r| (r.x as u32, r.y)).collect(),
+            ranges,
+            overlays,
+        }
+    }
+
+    /// Largest finite value of the function. Overlays are left out, so that a growing integral can't stretch the chart: they're clipped to it.
+    fn max(&self) -> f64 {
+        self.values
+            .iter()
+            .map(|(_, y)| *y)
+            .chain(self.ranges.iter().map(|r| r.max))
+            .filter(|y| y.is_finite())
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
 }
 
-pub struct MultiGraphOptions(Vec<GraphOptions>);
+#[derive(TypedBuilder, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MultiGraphOptions {
+    /// Colors of the axis, legend, background and grid, and of the default series.
+    #[builder(default)]
+    pub theme: Theme,
+    /// Options for each function, in the same order as the functions passed to the graph. Functions without options get the default ones, in the color of the theme at their position.
+    #[builder(default)]
+    pub series: Vec<GraphOptions>,
+    /// Areas to paint between two functions, given by their indices, e.g. `(0, 1, Fill::Character('.'.into()))`. Pairs with an index past the last function are skipped.
+    #[builder(default)]
+    pub fill_between: Vec<(usize, usize, Fill)>,
+    /// Draw each function on top of the cumulative sum of the previous ones.
+    #[builder(default)]
+    pub stack: Stack,
+    /// Mark the points where two functions cross (`◆`) with their coordinates.
+    #[builder(default)]
+    pub intersections: bool,
+}
+
+/// Stacked modes of [`MultiGraph`], where each function is drawn on top of the sum of the previous ones. Negative values count as 0.
+#[derive(Copy, Clone, Derivative, Debug, PartialEq, Eq)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Stack {
+    #[derivative(Default)]
+    /// **(Default)** Functions are drawn independently
+    None,
+    /// Paint each function as a band between the previous sum and its own, using its `fill` (or its color as background if it has none)
+    Area,
+    /// Draw a bar on every column, with a segment in the color of each function
+    Bar,
+}
+
+/// Replaces the values of each function by the cumulative sum of itself and all the previous ones. Non-finite values are left out: they stay non-finite and add nothing to the functions above.
+fn stack_values<T: Copy>(values: &mut [Vec<(T, f64)>]) {
+    let mut below: Vec<f64> = Vec::new();
+    for f_values in values.iter_mut() {
+        below.resize(below.len().max(f_values.len()), 0f64);
+        for ((_, y), below) in f_values.iter_mut().zip(below.iter_mut()) {
+            if y.is_finite() {
+                *y = y.max(0f64) + *below;
+                *below = *y;
+            }
+        }
+    }
+}
+
+/// Points of the derivative and integral of `f` enabled in its `options`, from 0 to `width`, each with the character it is drawn with.
+fn overlays<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+    f: &Function<X, Y, F>,
+    options: &GraphOptions,
+    width: u32,
+) -> Vec<(Character, Vec<(u32, f64)>)> {
+    let mut overlays = Vec::new();
+    if options.derivative {
+        overlays.push((
+            Character::EmptyBullet,
+            (0..=width)
+                .map(|x| (x, derivative_at(|t| f.at(t), x as f64)))
+                .collect(),
+        ));
+    }
+    if options.integral {
+        // Cumulative sum of the integral over each column
+        let mut total = 0f64;
+        let pts = (0..=width)
+            .map(|x| {
+                if x > 0 {
+                    total += integrate(
+                        |t| f.at(t),
+                        (x - 1) as f64,
+                        x as f64,
+                        Integration::default(),
+                        COLUMN_INTEGRAL_STEPS,
+                    );
+                }
+                (x, total)
+            })
+            .collect();
+        overlays.push((Character::Times, pts));
+    }
+    overlays
+}
 
 impl Default for MultiGraphOptions {
     fn default() -> MultiGraphOptions {
-        let colors = [
-            Color::Red,
-            Color::Blue,
-            Color::Green,
-            Color::Magenta,
-            Color::Cyan,
-            Color::Yellow,
-            Color::DarkRed,
-            Color::DarkBlue,
-            Color::DarkGreen,
-            Color::DarkMagenta,
-            Color::DarkCyan,
-            Color::DarkYellow,
-        ];
-        MultiGraphOptions(
-            (0..12)
-                .map(|i| GraphOptions {
-                    color: colors[i].into(),
-                    ..GraphOptions::default()
-                })
-                .collect(),
-        )
+        MultiGraphOptions::builder().build()
+    }
+}
+
+impl From<Vec<GraphOptions>> for MultiGraphOptions {
+    fn from(series: Vec<GraphOptions>) -> MultiGraphOptions {
+        MultiGraphOptions {
+            series,
+            ..MultiGraphOptions::default()
+        }
     }
 }
 
@@ -62,30 +239,56 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> MultiGraph<X, Y, F> {
         fs: Vec<Function<X, Y, F>>,
         width: u32,
         set_height: Option<u32>,
-        options: MultiGraphOptions,
+        mut options: MultiGraphOptions,
     ) -> MultiGraph<X, Y, F> {
-        // Get max y
-        let max = fs
+        options.theme.pad_series(&mut options.series, fs.len());
+        let samples = fs
             .iter()
-            .map(|f| {
-                f.rng(0, width)
-                    .into_iter()
-                    .reduce(f64::max)
-                    .unwrap_or_default()
-            })
-            .reduce(f64::max)
-            .unwrap_or_default()
-            .round() as u32;
+            .zip(options.series.iter())
+            .map(|(f, series)| Samples::new(f, series, width))
+            .collect();
+        MultiGraph::from_samples(&fs, samples, width, set_height, options)
+    }
+
+    fn from_samples(
+        fs: &[Function<X, Y, F>],
+        samples: Vec<Samples>,
+        width: u32,
+        set_height: Option<u32>,
+        options: MultiGraphOptions,
+    ) -> MultiGraph<X, Y, F> {
+        // Get max y, the top of the highest stack in stacked modes
+        let max = if options.stack == Stack::None {
+            samples
+                .iter()
+                .map(Samples::max)
+                .reduce(f64::max)
+                .filter(|max| max.is_finite())
+                .unwrap_or_default()
+        } else {
+            let mut values: Vec<Vec<(u32, f64)>> =
+                samples.iter().map(|s| s.values.clone()).collect();
+            stack_values(&mut values);
+            values
+                .iter()
+                .flatten()
+                .map(|(_, y)| *y)
+                .filter(|y| y.is_finite())
+                .reduce(f64::max)
+                .unwrap_or_default()
+        }
+        .round() as u32;
         // Get digits of maximum number
         let max_height_digits =
-            successors(Some(max), |&n| (n >= 10).then(|| n / 10)).count() as u32;
+            successors(Some(max), |&n| (n >= 10).then_some(n / 10)).count() as u32;
 
         let height = match set_height {
             Some(h) => h,
             None => max + 1,
         };
-        MultiGraph {
-            functions: fs,
+        let mut graph = MultiGraph {
+            samples,
+            annotations: Vec::new(),
             widths: GraphWidths {
                 total: width,
                 graph: width - max_height_digits,
@@ -94,7 +297,10 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> MultiGraph<X, Y, F> {
             height,
             graph_height: height - 1,
             options,
-        }
+            _functions: PhantomData,
+        };
+        graph.annotations = graph.annotate(fs);
+        graph
     }
 
     pub fn with_options_screen(
@@ -105,16 +311,94 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> MultiGraph<X, Y, F> {
         MultiGraph::with_options(f, w_screen as u32, None, options)
     }
 
+    /// Same as [`MultiGraph::with_options`], evaluating the functions and their columns in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn with_options_parallel(
+        fs: Vec<Function<X, Y, F>>,
+        width: u32,
+        set_height: Option<u32>,
+        mut options: MultiGraphOptions,
+    ) -> MultiGraph<X, Y, F>
+    where
+        X: Sync,
+        Y: Sync,
+        F: Sync,
+    {
+        options.theme.pad_series(&mut options.series, fs.len());
+        let samples = fs
+            .par_iter()
+            .zip(options.series.par_iter())
+            .map(|(f, series)| Samples::new_parallel(f, series, width))
+            .collect();
+        MultiGraph::from_samples(&fs, samples, width, set_height, options)
+    }
+
     pub fn draw(&self) {
-        let mut scr = Screen::new(self.widths.total, self.height);
+        draw_screen(self.render());
+    }
+
+    pub(crate) fn render(&self) -> Screen {
+        let styles = self.styles();
+        let legend = self.legend(&styles);
+        let mut scr = Screen::new(self.widths.total, self.height + legend.len() as u32);
 
         self.draw_axis(&mut scr);
-        if self.options.0.get(0).unwrap().height_legend {
+        if self
+            .options
+            .series
+            .first()
+            .map_or(true, |series| series.height_legend)
+        {
             self.draw_height_legend(&mut scr);
         }
-        self.draw_functions(&mut scr);
+        self.options.theme.draw_grid(
+            &mut scr,
+            (self.widths.height_legend as i32, self.graph_height as i32),
+            self.widths.graph as i32,
+        );
+        let mut pts: Vec<Vec<(u32, f64)>> = self
+            .samples
+            .iter()
+            .map(|s| {
+                s.values
+                    .iter()
+                    .copied()
+                    .filter(|(x, _)| *x <= self.widths.graph)
+                    .collect()
+            })
+            .collect();
+        // Below the functions, which are drawn on top
+        self.draw_overlays(&mut scr);
+        match self.options.stack {
+            Stack::None => {
+                self.draw_fills(&mut scr, &pts);
+                self.draw_ranges(&mut scr, &styles);
+                self.draw_functions(&mut scr, &pts, &styles);
+            }
+            Stack::Area => {
+                stack_values(&mut pts);
+                self.draw_stacked(&mut scr, &pts, &styles);
+                self.draw_functions(&mut scr, &pts, &styles);
+            }
+            Stack::Bar => {
+                stack_values(&mut pts);
+                self.draw_stacked(&mut scr, &pts, &styles);
+            }
+        }
+        self.draw_legend(&mut scr, &legend);
+
+        draw_annotations(
+            &mut scr,
+            &self.annotations,
+            (
+                self.widths.height_legend as i32,
+                self.widths.total as i32 - 1,
+            ),
+            (0, self.graph_height as i32),
+        );
+        self.options.theme.paint_background(&mut scr);
 
-        scr.draw();
+        scr
     }
 
     fn draw_axis(&self, scr: &mut Screen) {
@@ -123,13 +407,13 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> MultiGraph<X, Y, F> {
             (self.widths.height_legend + 1) as i32,
             self.graph_height as i32,
             self.widths.graph as i32,
-            pixel::pxl('_'),
+            pixel::pxl_fg('_', self.options.theme.axis.into()),
         );
         scr.v_line(
             self.widths.height_legend as i32,
             0,
-            self.height as i32,
-            pixel::pxl('|'),
+            self.graph_height as i32,
+            pixel::pxl_fg('|', self.options.theme.axis.into()),
         );
     }
 
@@ -139,32 +423,285 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> MultiGraph<X, Y, F> {
                 scr.set_pxl(
                     index as i32,
                     (self.graph_height - h) as i32,
-                    pixel::pxl(digit),
+                    pixel::pxl_fg(digit, self.options.theme.legend.into()),
                 );
             }
         }
     }
 
-    fn draw_functions(&self, scr: &mut Screen) {
-        for (i, f) in self.functions.iter().enumerate() {
-            // Draw points
-            for (x, y) in f
-                .rng_x(0, self.widths.graph)
-                .into_iter()
-                .map(|(x, y)| (x, y.round() as u32))
+    fn draw_fills(&self, scr: &mut Screen, pts: &[Vec<(u32, f64)>]) {
+        let row = |y: f64| (self.graph_height as f64 - y).round() as i32;
+        // Area under each function
+        for (i, f_pts) in pts.iter().enumerate() {
+            let options = self.options.series.get(i).unwrap();
+            for (x, y) in f_pts {
+                for r in row(*y).saturating_add(1).max(0)..self.graph_height as i32 {
+                    options.fill.paint(
+                        scr,
+                        (x + self.widths.height_legend) as i32,
+                        r,
+                        options.color,
+                    );
+                }
+            }
+        }
+        // Area between pairs of functions
+        for (i, j, fill) in self.options.fill_between.iter() {
+            let (Some(pts_i), Some(pts_j)) = (pts.get(*i), pts.get(*j)) else {
+                continue;
+            };
+            let color = self.options.series[*i].color;
+            for ((x, y_i), (_, y_j)) in pts_i.iter().zip(pts_j.iter()) {
+                let (top, bottom) = (row(y_i.max(*y_j)), row(y_i.min(*y_j)));
+                for r in top.saturating_add(1).max(0)..bottom.min(self.graph_height as i32) {
+                    fill.paint(scr, (x + self.widths.height_legend) as i32, r, color);
+                }
+            }
+        }
+    }
+
+    /// Paints the band of each function in stacked modes, `pts` must already hold the cumulative values.
+    fn draw_stacked(
+        &self,
+        scr: &mut Screen,
+        pts: &[Vec<(u32, f64)>],
+        styles: &[(Character, LineStyle)],
+    ) {
+        let row = |y: f64| (self.graph_height as f64 - y).round() as i32;
+        let distinct = self.distinct_colors();
+        for (i, f_pts) in pts.iter().enumerate() {
+            let options = self.options.series.get(i).unwrap();
+            let fill = match (self.options.stack, options.fill) {
+                (Stack::Bar, _) if distinct => Fill::Character('█'.into()),
+                (Stack::Bar, _) => Fill::Character(styles[i].0),
+                (_, Fill::None) => Fill::Background(options.color),
+                (_, fill) => fill,
+            };
+            for (k, (x, y)) in f_pts.iter().enumerate().filter(|(_, (_, y))| y.is_finite()) {
+                // Top of the closest function below with a value here
+                let below = pts[..i]
+                    .iter()
+                    .rev()
+                    .filter_map(|f| f.get(k).map(|(_, y)| *y))
+                    .find(|y| y.is_finite())
+                    .unwrap_or_default();
+                // Bars include the top of the segment, areas leave it for the function point
+                let top = match self.options.stack {
+                    Stack::Bar => row(*y),
+                    _ => row(*y).saturating_add(1),
+                };
+                for r in top.max(0)..row(below).min(self.graph_height as i32) {
+                    fill.paint(
+                        scr,
+                        (x + self.widths.height_legend) as i32,
+                        r,
+                        options.color,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Roots and extrema of the functions that have them enabled, and their intersections if enabled, in the plotted range.
+    fn annotate(&self, fs: &[Function<X, Y, F>]) -> Vec<Annotation> {
+        let end = self.widths.graph as f64;
+        let annotation = |(x, y): (f64, f64), marker: char, color: ColorWrapper| Annotation {
+            position: (
+                x.round() as i32 + self.widths.height_legend as i32,
+                (self.graph_height as f64 - y).round() as i32,
+            ),
+            marker,
+            color,
+            point: (x, y),
+        };
+        let mut annotations = Vec::new();
+        for (f, series) in fs.iter().zip(self.options.series.iter()) {
+            if series.roots {
+                annotations.extend(
+                    f.roots(0f64, end)
+                        .into_iter()
+                        .map(|x| annotation((x, 0f64), ROOT_MARKER, series.color)),
+                );
+            }
+            if series.extrema {
+                annotations.extend(
+                    f.extrema(0f64, end)
+                        .into_iter()
+                        .map(|e| annotation((e.x, e.y), extremum_marker(e.kind), series.color)),
+                );
+            }
+        }
+        if self.options.intersections {
+            for (i, f) in fs.iter().enumerate() {
+                let color = self.options.series.get(i).unwrap().color;
+                for g in fs.iter().skip(i + 1) {
+                    annotations.extend(
+                        f.intersections(g, 0f64, end)
+                            .into_iter()
+                            .map(|pt| annotation(pt, INTERSECTION_MARKER, color)),
+                    );
+                }
+            }
+        }
+        annotations
+    }
+
+    /// Derivatives and integrals of the functions that have them enabled.
+    fn draw_overlays(&self, scr: &mut Screen) {
+        for (samples, series) in self.samples.iter().zip(self.options.series.iter()) {
+            for (character, pts) in samples.overlays.iter() {
+                let pxl = pixel::pxl_fg(character.as_char(), series.color.into());
+                for (x, y) in pts.iter().filter(|(x, _)| *x <= self.widths.graph) {
+                    let row = (self.graph_height as f64 - y).round();
+                    if row >= 0f64 && row <= self.graph_height as f64 {
+                        scr.set_pxl((x + self.widths.height_legend) as i32, row as i32, pxl);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whole range of values within each column of the functions sampled adaptively.
+    fn draw_ranges(&self, scr: &mut Screen, styles: &[(Character, LineStyle)]) {
+        let row = |y: f64| (self.graph_height as f64 - y).round() as i32;
+        for ((samples, series), (character, line_style)) in self
+            .samples
+            .iter()
+            .zip(self.options.series.iter())
+            .zip(styles.iter())
+        {
+            let pxl = pixel::pxl_fg(character.as_char(), series.color.into());
+            for r in samples.ranges.iter().filter(|r| {
+                !r.min.is_nan() && r.x <= self.widths.graph as f64 && line_style.draws(r.x as u32)
+            }) {
+                for y in row(r.max).max(0)..=row(r.min).min(self.graph_height as i32) {
+                    scr.set_pxl(r.x as i32 + self.widths.height_legend as i32, y, pxl);
+                }
+            }
+        }
+    }
+
+    fn draw_functions(
+        &self,
+        scr: &mut Screen,
+        pts: &[Vec<(u32, f64)>],
+        styles: &[(Character, LineStyle)],
+    ) {
+        for (i, f_pts) in pts.iter().enumerate() {
+            let (character, line_style) = styles[i];
+            // Draw points, skipping the ones outside of the chart
+            for (x, row) in f_pts
+                .iter()
+                .filter(|(x, _)| line_style.draws(*x))
+                .map(|(x, y)| (*x, self.graph_height as f64 - y.round()))
+                .filter(|(_, row)| (0f64..=self.graph_height as f64).contains(row))
             {
                 scr.set_pxl(
                     (x + self.widths.height_legend) as i32,
-                    (self.graph_height - y) as i32, // TODO Allow selecting approximation method: round, ceil or cast (as)
+                    row as i32, // TODO Allow selecting approximation method: round, ceil or cast (as)
                     // Can also put a space (or empty box or something) and color bg
                     pixel::pxl_fg(
-                        self.options.0.get(i).unwrap().character.as_char(),
-                        self.options.0.get(i).unwrap().color.into(),
+                        character.as_char(),
+                        self.options.series.get(i).unwrap().color.into(),
                     ),
                 )
             }
         }
     }
+
+    /// Whether the colors of the functions, as shown by the terminal, are all different.
+    fn distinct_colors(&self) -> bool {
+        let support = ColorSupport::current();
+        let colors: Vec<Color> = self.options.series[..self.samples.len()]
+            .iter()
+            .map(|s| s.color.downgrade(support).into())
+            .collect();
+        colors
+            .iter()
+            .enumerate()
+            .all(|(i, c)| !colors[..i].contains(c))
+    }
+
+    /// Character and line style of each function. When their colors can't tell them apart, as with `NO_COLOR` or when the output isn't a terminal, the ones left as default are given distinct ones by their position.
+    fn styles(&self) -> Vec<(Character, LineStyle)> {
+        let distinct = self.distinct_colors();
+        self.options.series[..self.samples.len()]
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                if distinct {
+                    return (s.character, s.line_style);
+                }
+                let character = match s.character {
+                    Character::Star => MARKERS[i % MARKERS.len()],
+                    character => character,
+                };
+                let line_style = match s.line_style {
+                    LineStyle::Solid => LINE_STYLES[i % LINE_STYLES.len()],
+                    line_style => line_style,
+                };
+                (character, line_style)
+            })
+            .collect()
+    }
+
+    /// Entries of the legend, the index of each function and its line sample and label (its position if it has none), in rows that fit in the width. Empty if no function has a label.
+    fn legend(&self, styles: &[(Character, LineStyle)]) -> Vec<Vec<(usize, String)>> {
+        let series = &self.options.series[..self.samples.len()];
+        if series.iter().all(|s| s.label.is_none()) {
+            return Vec::new();
+        }
+        let distinct = self.distinct_colors();
+        let left = self.widths.height_legend as usize + 1;
+        let mut rows: Vec<Vec<(usize, String)>> = Vec::new();
+        let mut end = 0;
+        for (i, (s, (character, line_style))) in series.iter().zip(styles.iter()).enumerate() {
+            let label = s.label.clone().unwrap_or_else(|| (i + 1).to_string());
+            // Bars are drawn on every column, in whole blocks if colors tell them apart
+            let sample = match self.options.stack {
+                Stack::Bar if distinct => LineStyle::Solid.sample('█'),
+                Stack::Bar => LineStyle::Solid.sample(character.as_char()),
+                _ => line_style.sample(character.as_char()),
+            };
+            let entry = format!("{} {}", sample, label);
+            let len = entry.chars().count();
+            match rows.last_mut() {
+                Some(row) if end + LEGEND_GAP + len <= self.widths.total as usize => {
+                    end += LEGEND_GAP + len;
+                    row.push((i, entry));
+                }
+                _ => {
+                    end = left + len;
+                    rows.push(vec![(i, entry)]);
+                }
+            }
+        }
+        rows
+    }
+
+    /// Draws the legend below the x axis, the line samples in the color of their function.
+    fn draw_legend(&self, scr: &mut Screen, legend: &[Vec<(usize, String)>]) {
+        for (r, row) in legend.iter().enumerate() {
+            let mut x = self.widths.height_legend as i32 + 1;
+            for (i, entry) in row {
+                let color = self.options.series.get(*i).unwrap().color;
+                for (k, c) in entry.chars().enumerate() {
+                    let color = if k < LINE_SAMPLE_WIDTH {
+                        color
+                    } else {
+                        self.options.theme.legend
+                    };
+                    scr.set_pxl(
+                        x,
+                        self.height as i32 + r as i32,
+                        pixel::pxl_fg(c, color.into()),
+                    );
+                    x += 1;
+                }
+                x += LEGEND_GAP as i32;
+            }
+        }
+    }
 }
 
 impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> fmt::Display for MultiGraph<X, Y, F> {
@@ -173,3 +710,238 @@ impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> fmt::Display for MultiGraph<X, Y, F> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::plain_text;
+    use console_engine::Color;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Two functions in the same color, labelled `a` and `b`.
+    fn same_color() -> MultiGraph<f64, f64, impl Fn(f64) -> f64> {
+        let series = ["a", "b"]
+            .into_iter()
+            .map(|label| {
+                GraphOptions::builder()
+                    .color(Color::Green.into())
+                    .label(label)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        MultiGraph::with_options(vec![func(1f64), func(3f64)], 20, None, series.into())
+    }
+
+    #[test]
+    fn functions_without_options_get_theme_colors() {
+        let options = MultiGraphOptions::from(vec![GraphOptions::builder()
+            .color(Color::Green.into())
+            .build()]);
+        let graph = MultiGraph::with_options(vec![func(1f64), func(2f64)], 20, None, options);
+        assert_eq!(graph.samples.len(), 2);
+        assert_eq!(
+            Color::from(graph.options.series[1].color),
+            Color::from(Theme::default().color(1))
+        );
+        // Both functions are drawn, on the rows of their values
+        let scr = graph.render();
+        for (row, color) in [
+            (0, Color::from(Theme::default().color(1))),
+            (1, Color::Green),
+        ] {
+            let pxl = scr.get_pxl(1, row).unwrap();
+            assert!(pxl.chr != ' ' && pxl.fg == color, "row {row}");
+        }
+    }
+
+    #[test]
+    fn fill_between_skips_missing_functions() {
+        let graph = |fill_between| {
+            let options = MultiGraphOptions::builder()
+                .fill_between(fill_between)
+                .build();
+            MultiGraph::with_options(vec![func(1f64), func(3f64)], 20, Some(6), options)
+        };
+        let plain = plain_text(&graph(vec![]).render());
+        let dots = Fill::Character('.'.into());
+        assert_eq!(
+            plain_text(&graph(vec![(0, 2, dots), (5, 1, dots)]).render()),
+            plain
+        );
+        assert_ne!(plain_text(&graph(vec![(0, 1, dots)]).render()), plain);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialized_palette_colors_series() {
+        let options: MultiGraphOptions =
+            toml::from_str("[theme]\npalette = [\"green\", \"#ff8000\"]\n").unwrap();
+        let graph = MultiGraph::with_options(vec![func(1f64), func(2f64)], 20, None, options);
+        assert_eq!(Color::from(graph.options.series[0].color), Color::Green);
+        assert_eq!(graph.options.series[1].color.to_string(), "#ff8000");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn options_round_trip() {
+        let options = MultiGraphOptions::builder()
+            .theme(Theme::solarized())
+            .series(vec![
+                GraphOptions::builder()
+                    .color(ColorWrapper::rgb(255, 128, 0))
+                    .character('o'.into())
+                    .line_style(LineStyle::Dashed)
+                    .label("a")
+                    .scales(Scales::from((2f64, 0.5)))
+                    .fill(Fill::Background(ColorWrapper::ansi(208)))
+                    .roots(true)
+                    .sampling(Sampling::Adaptive)
+                    .build(),
+                GraphOptions::builder()
+                    .color(Color::Blue.into())
+                    .height_legend(false)
+                    .build(),
+            ])
+            .fill_between(vec![(0, 1, Fill::Character('.'.into()))])
+            .stack(Stack::Area)
+            .intersections(true)
+            .build();
+        let text = toml::to_string(&options).unwrap();
+        let parsed: MultiGraphOptions = toml::from_str(&text).unwrap();
+        assert_eq!(toml::to_string(&parsed).unwrap(), text);
+
+        assert_eq!(parsed.theme.grid, Theme::solarized().grid);
+        assert_eq!(parsed.series.len(), 2);
+        assert_eq!(parsed.series[0].color.to_string(), "#ff8000");
+        assert_eq!(parsed.series[0].line_style, LineStyle::Dashed);
+        assert_eq!(parsed.series[0].label.as_deref(), Some("a"));
+        assert_eq!(parsed.series[0].scales.x, 2f64);
+        assert_eq!(parsed.series[0].sampling, Sampling::Adaptive);
+        assert!(!parsed.series[1].height_legend);
+        assert!(matches!(
+            parsed.fill_between[..],
+            [(0, 1, Fill::Character(_))]
+        ));
+        assert!(matches!(parsed.stack, Stack::Area));
+        assert!(parsed.intersections);
+    }
+
+    #[test]
+    fn stacks_leave_out_non_finite_values() {
+        let mut values = vec![
+            vec![(0, 1f64), (1, f64::INFINITY), (2, -1f64)],
+            vec![(0, 2f64), (1, 2f64), (2, f64::NAN)],
+            vec![(0, 3f64), (1, 3f64), (2, 3f64)],
+        ];
+        stack_values(&mut values);
+        assert_eq!(values[0][..1], [(0, 1f64)]);
+        assert_eq!(values[0][2], (2, 0f64));
+        assert_eq!(values[1][..2], [(0, 3f64), (1, 2f64)]);
+        assert!(values[1][2].1.is_nan());
+        assert_eq!(values[2], [(0, 6f64), (1, 5f64), (2, 3f64)]);
+
+        let f = |x: f64| 1f64 / x;
+        let options = MultiGraphOptions::builder().stack(Stack::Area).build();
+        let graph =
+            MultiGraph::with_options(vec![Function::new(f), Function::new(f)], 20, None, options);
+        assert_eq!(graph.height, 3);
+    }
+
+    #[test]
+    fn values_outside_of_the_chart_are_skipped() {
+        let options = MultiGraphOptions::builder().stack(Stack::Area).build();
+        let graph = MultiGraph::with_options(vec![func(3f64), func(4f64)], 20, Some(5), options);
+        let text = plain_text(&graph.render());
+        assert_eq!(text.lines().count(), 5);
+
+        let f = |x: f64| 1f64 / x;
+        let graph = MultiGraph::new(vec![Function::new(f), Function::new(f)], 20, None);
+        graph.render();
+        let graph = MultiGraph::new(vec![func(-3f64), func(2f64)], 20, Some(4));
+        let text = plain_text(&graph.render());
+        let rows: Vec<&str> = text.lines().collect();
+        // Only the positive function is drawn, on the row of its value
+        assert_eq!(rows[1].chars().count(), 20);
+        assert_eq!(rows[3], format!("0|{}", "_".repeat(18)));
+    }
+
+    #[test]
+    fn adaptive_sampling_evaluates_once() {
+        let count = |sampling: Option<Sampling>| {
+            let evaluations = AtomicU32::new(0);
+            let f = Function::new(|x: f64| {
+                evaluations.fetch_add(1, Ordering::Relaxed);
+                x.sin() * 3f64 + 3f64
+            });
+            match sampling {
+                Some(sampling) => {
+                    let options = MultiGraphOptions::from(vec![GraphOptions::builder()
+                        .sampling(sampling)
+                        .build()]);
+                    MultiGraph::with_options(vec![f], 20, None, options);
+                }
+                None => {
+                    f.rng_adaptive(0, 20, &Scales::default());
+                }
+            }
+            evaluations.into_inner()
+        };
+        assert_eq!(count(Some(Sampling::Uniform)), 21);
+        assert_eq!(count(Some(Sampling::Adaptive)), count(None));
+    }
+
+    #[test]
+    fn same_colors_get_distinct_styles() {
+        let styles = same_color().styles();
+        assert_eq!(styles[0].0.as_char(), Character::Star.as_char());
+        assert_eq!(styles[0].1, LineStyle::Solid);
+        assert_eq!(styles[1].0.as_char(), Character::Bullet.as_char());
+        assert_eq!(styles[1].1, LineStyle::Dashed);
+    }
+
+    #[test]
+    fn legend_shows_styles() {
+        let text = plain_text(&same_color().render());
+        let rows: Vec<&str> = text.lines().collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[4].trim(), "⁕⁕⁕⁕⁕ a   ••• • b");
+        // Dashed line, three columns out of every four
+        assert!(rows[0].contains("••• ••• •••"));
+    }
+
+    #[test]
+    fn legend_wraps() {
+        let series = (0..3)
+            .map(|i| {
+                GraphOptions::builder()
+                    .label(format!("series {}", i))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        let graph = MultiGraph::with_options(
+            vec![func(1f64), func(2f64), func(3f64)],
+            20,
+            None,
+            series.into(),
+        );
+        let legend = graph.legend(&graph.styles());
+        assert_eq!(legend.len(), 3);
+        assert!(legend.iter().all(|row| row.len() == 1));
+    }
+
+    #[test]
+    fn overlays_are_clipped_to_the_functions() {
+        let options = vec![GraphOptions::builder().integral(true).build()];
+        let graph = MultiGraph::with_options(
+            vec![Function::new(|x: f64| 5f64 + 4f64 * (x / 3f64).sin())],
+            60,
+            None,
+            options.into(),
+        );
+        assert_eq!(graph.graph_height, 9);
+    }
+
+    fn func(v: f64) -> Function<f64, f64, impl Fn(f64) -> f64> {
+        Function::new(move |_: f64| v)
+    }
+}
@@ -0,0 +1,57 @@
+use derivative::Derivative;
+use std::marker::PhantomData;
+
+use crate::traits::AsF64;
+
+/// Terminal cells are roughly twice as tall as they are wide, so x is stretched by this factor for curves to keep their shape.
+pub const CELL_ASPECT_RATIO: f64 = 2f64;
+
+/// Parametric curve `t -> (x(t), y(t))`, sampled `samples` times evenly from `t_i` to `t_f`.
+///
+/// Drawn with [`Graph::parametric`](struct.Graph.html#method.parametric) on the same axes as functions, which start at the origin: points with a negative x or y are not drawn, so shift the curve into the first quadrant, e.g. `|t| (10f64 + t.cos(), 10f64 + t.sin())`.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Parametric<X: AsF64, Y: AsF64, F: Fn(f64) -> (X, Y)> {
+    #[derivative(Debug = "ignore")]
+    f: F,
+    pub t_i: f64,
+    pub t_f: f64,
+    pub samples: u32,
+    /// Height of a terminal cell relative to its width, x coordinates are multiplied by it when drawn. Defaults to [`CELL_ASPECT_RATIO`], set it to `1.0` to disable the correction.
+    pub aspect_ratio: f64,
+    #[derivative(Debug = "ignore")]
+    _x: PhantomData<X>,
+    #[derivative(Debug = "ignore")]
+    _y: PhantomData<Y>,
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(f64) -> (X, Y)> Parametric<X, Y, F> {
+    pub fn new(f: F, t_i: f64, t_f: f64, samples: u32) -> Parametric<X, Y, F> {
+        Parametric {
+            f,
+            t_i,
+            t_f,
+            samples,
+            aspect_ratio: CELL_ASPECT_RATIO,
+            _x: PhantomData,
+            _y: PhantomData,
+        }
+    }
+
+    pub fn at(&self, t: f64) -> (f64, f64) {
+        let (x, y) = (self.f)(t);
+        (x.as_f64(), y.as_f64())
+    }
+
+    /// Points of the curve at each sample of `t`.
+    pub fn pts(&self) -> Vec<(f64, f64)> {
+        let step = if self.samples > 1 {
+            (self.t_f - self.t_i) / (self.samples - 1) as f64
+        } else {
+            0f64
+        };
+        (0..self.samples)
+            .map(|i| self.at(self.t_i + i as f64 * step))
+            .collect()
+    }
+}
@@ -0,0 +1,198 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+
+use crate::bar_chart::format_value;
+use crate::graph::GraphWidths;
+use crate::parametric::CELL_ASPECT_RATIO;
+use crate::theme::Theme;
+use crate::types::Domain;
+
+/// Rows between two labels of the y axis legend.
+const Y_LEGEND_STEP: u32 = 4;
+
+/// Layout shared by the charts drawn over a rectangular [`Domain`] of the plane: y legend on the left, plotting area and x legend on the last row.
+///
+/// The plotting area is split in `cols() x rows()` cells, each one covering the same rectangle of the domain.
+pub(crate) struct Plane {
+    pub domain: Domain,
+    pub widths: GraphWidths,
+    pub height: u32,
+    /// Index of the last row of the plotting area, where the x axis is drawn.
+    pub graph_height: u32,
+}
+
+impl Plane {
+    /// `set_height` is the height of the plotting area, by default the one keeping the same scale on both axes.
+    pub fn new(domain: Domain, width: u32, set_height: Option<u32>) -> Plane {
+        let rows = |height_legend: u32| match set_height {
+            Some(h) => h.max(1),
+            None => (width.saturating_sub(height_legend + 1) as f64 * domain.height()
+                / domain.width()
+                / CELL_ASPECT_RATIO)
+                .round()
+                .max(1f64) as u32,
+        };
+        let legend_width = |rows: u32| {
+            y_labels(&domain, rows)
+                .iter()
+                .map(|(_, label)| label.chars().count() as u32)
+                .max()
+                .unwrap_or_default()
+        };
+        // The legend width depends on the labelled rows, which depend on the width left for the plot
+        let height_legend = legend_width(rows(legend_width(rows(0))));
+        let rows = rows(height_legend);
+        Plane {
+            domain,
+            widths: GraphWidths {
+                total: width,
+                graph: width.saturating_sub(height_legend + 1),
+                height_legend,
+            },
+            height: rows + 1,
+            graph_height: rows - 1,
+        }
+    }
+
+    pub fn cols(&self) -> u32 {
+        self.widths.graph
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.graph_height + 1
+    }
+
+    /// Point of the domain at continuous plotting area coordinates, `(0, 0)` being the top left corner and `(cols(), rows())` the bottom right one.
+    pub fn to_plane(&self, u: f64, v: f64) -> (f64, f64) {
+        (
+            self.domain.x.0 + u / self.cols() as f64 * self.domain.width(),
+            self.domain.y.1 - v / self.rows() as f64 * self.domain.height(),
+        )
+    }
+
+    /// Continuous plotting area coordinates of a point of the domain, inverse of [`Plane::to_plane`].
+    pub fn to_area(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x - self.domain.x.0) / self.domain.width() * self.cols() as f64,
+            (self.domain.y.1 - y) / self.domain.height() * self.rows() as f64,
+        )
+    }
+
+    /// Center of the cell at column `col` and row `row` of the plotting area.
+    pub fn center(&self, col: u32, row: u32) -> (f64, f64) {
+        self.to_plane(col as f64 + 0.5, row as f64 + 0.5)
+    }
+
+    /// Screen position of the cell holding continuous plotting area coordinates (`u`, `v`), `None` outside of the plotting area.
+    pub fn screen_at(&self, u: f64, v: f64) -> Option<(i32, i32)> {
+        let (col, row) = (u.floor(), v.floor());
+        // Points right on the far border belong to the last cell
+        let col = if u == self.cols() as f64 {
+            col - 1f64
+        } else {
+            col
+        };
+        let row = if v == self.rows() as f64 {
+            row - 1f64
+        } else {
+            row
+        };
+        if col < 0f64 || row < 0f64 || col >= self.cols() as f64 || row >= self.rows() as f64 {
+            return None;
+        }
+        Some((
+            col as i32 + self.widths.height_legend as i32 + 1,
+            row as i32,
+        ))
+    }
+
+    /// Screen position of the cell holding the point (`x`, `y`) of the domain.
+    pub fn screen(&self, x: f64, y: f64) -> Option<(i32, i32)> {
+        let (u, v) = self.to_area(x, y);
+        self.screen_at(u, v)
+    }
+
+    /// Draws the borders, the lines through the origin and the grid of the theme.
+    pub fn draw_axis(&self, scr: &mut Screen, theme: &Theme) {
+        // Lines through the origin, if it is inside the domain
+        let origin = pixel::pxl_fg('·', theme.grid_color.into());
+        if let Some((x, _)) = self.screen(0f64, self.domain.y.0) {
+            scr.v_line(x, 0, self.graph_height as i32, origin);
+        }
+        if let Some((_, y)) = self.screen(self.domain.x.0, 0f64) {
+            scr.h_line(
+                self.widths.height_legend as i32 + 1,
+                y,
+                self.widths.total as i32 - 1,
+                origin,
+            );
+        }
+        // Borders
+        scr.h_line(
+            (self.widths.height_legend + 1) as i32,
+            self.graph_height as i32,
+            self.widths.total as i32 - 1,
+            pixel::pxl_fg('_', theme.axis.into()),
+        );
+        scr.v_line(
+            self.widths.height_legend as i32,
+            0,
+            self.graph_height as i32,
+            pixel::pxl_fg('|', theme.axis.into()),
+        );
+        theme.draw_grid(
+            scr,
+            (self.widths.height_legend as i32, self.graph_height as i32),
+            self.widths.total as i32 - 1,
+        );
+    }
+
+    pub fn draw_legends(&self, scr: &mut Screen, theme: &Theme) {
+        self.draw_y_legend(scr, theme);
+        self.draw_x_legend(scr, theme);
+    }
+
+    pub fn draw_y_legend(&self, scr: &mut Screen, theme: &Theme) {
+        for (row, label) in y_labels(&self.domain, self.rows()) {
+            theme.print_legend(scr, 0, row as i32, &label);
+        }
+    }
+
+    pub fn draw_x_legend(&self, scr: &mut Screen, theme: &Theme) {
+        // x values at both ends, and the origin in between
+        let start = self.widths.height_legend as i32 + 1;
+        let legend_row = self.graph_height as i32 + 1;
+        theme.print_legend(scr, start, legend_row, &format_value(self.domain.x.0));
+        let max = format_value(self.domain.x.1);
+        theme.print_legend(
+            scr,
+            self.widths.total as i32 - max.chars().count() as i32,
+            legend_row,
+            &max,
+        );
+        if let Some((x, _)) = self.screen(0f64, self.domain.y.0) {
+            if x > start + format_value(self.domain.x.0).len() as i32
+                && x < self.widths.total as i32 - max.len() as i32 - 1
+            {
+                theme.print_legend(scr, x, legend_row, "0");
+            }
+        }
+    }
+}
+
+/// Labels of the y axis legend for a plotting area of `rows` rows: the values at the top, the bottom and every few rows in between.
+fn y_labels(domain: &Domain, rows: u32) -> Vec<(u32, String)> {
+    (0..rows)
+        .filter(|r| r % Y_LEGEND_STEP == 0 || *r == rows - 1)
+        .map(|row| {
+            let y = if row == 0 {
+                domain.y.1
+            } else if row == rows - 1 {
+                domain.y.0
+            } else {
+                domain.y.1 - (row as f64 + 0.5) / rows as f64 * domain.height()
+            };
+            (row, format_value(y))
+        })
+        .collect()
+}
@@ -0,0 +1,224 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use derivative::Derivative;
+use std::f64::consts::TAU;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::bar_chart::format_value;
+use crate::color::draw_screen;
+use crate::function::Function;
+use crate::graph::GraphOptions;
+use crate::parametric::CELL_ASPECT_RATIO;
+use crate::theme::Theme;
+use crate::traits::AsF64;
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct PolarOptions {
+    /// Color and character of the curve.
+    #[builder(default)]
+    pub graph: GraphOptions,
+    #[derivative(Default(value = "0f64"))]
+    #[builder(default = 0f64)]
+    pub theta_i: f64,
+    #[derivative(Default(value = "TAU"))]
+    #[builder(default = TAU)]
+    pub theta_f: f64,
+    /// Number of values of θ the function is evaluated at.
+    #[derivative(Default(value = "720"))]
+    #[builder(default = 720)]
+    pub samples: u32,
+    /// Radius at the border of the chart, the maximum absolute value of the function if `None`.
+    #[builder(default, setter(strip_option))]
+    pub max_radius: Option<f64>,
+    /// Draw concentric rings and angle spokes behind the curve.
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub grid: bool,
+    #[derivative(Default(value = "4"))]
+    #[builder(default = 4)]
+    pub rings: u32,
+    #[derivative(Default(value = "8"))]
+    #[builder(default = 8)]
+    pub spokes: u32,
+    /// Colors of the grid, its labels, the origin and the background. The curve uses `graph`.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+/// Graph of a function `r = f(θ)` on polar axes, with θ in radians.
+pub struct PolarGraph {
+    width: u32,
+    height: u32,
+    /// Radius of the chart in rows, columns are stretched by [`CELL_ASPECT_RATIO`].
+    radius: f64,
+    max_radius: f64,
+    pts: Vec<(f64, f64)>,
+    options: PolarOptions,
+}
+
+impl PolarGraph {
+    /// `set_height` defaults to the height needed for the chart to be as wide as `width`.
+    pub fn new<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+        f: Function<X, Y, F>,
+        width: u32,
+        set_height: Option<u32>,
+    ) -> PolarGraph {
+        PolarGraph::with_options(f, width, set_height, PolarOptions::default())
+    }
+
+    pub fn new_screen<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(f: Function<X, Y, F>) -> PolarGraph {
+        PolarGraph::with_options_screen(f, PolarOptions::default())
+    }
+
+    pub fn with_options<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+        f: Function<X, Y, F>,
+        width: u32,
+        set_height: Option<u32>,
+        options: PolarOptions,
+    ) -> PolarGraph {
+        let step = if options.samples > 1 {
+            (options.theta_f - options.theta_i) / (options.samples - 1) as f64
+        } else {
+            0f64
+        };
+        // Generate (r, θ) pairs
+        let pts: Vec<(f64, f64)> = (0..options.samples)
+            .map(|i| {
+                let theta = options.theta_i + i as f64 * step;
+                (f.at(theta), theta)
+            })
+            .filter(|(r, _)| r.is_finite())
+            .collect();
+        let max_radius = options.max_radius.unwrap_or_else(|| {
+            pts.iter()
+                .map(|(r, _)| r.abs())
+                .fold(0f64, f64::max)
+                .max(f64::EPSILON)
+        });
+        let height = match set_height {
+            Some(h) => h,
+            None => (width as f64 / CELL_ASPECT_RATIO).round() as u32 + 1,
+        };
+        PolarGraph {
+            width,
+            height,
+            radius: (height.saturating_sub(1) as f64 / 2f64)
+                .min(width.saturating_sub(1) as f64 / 2f64 / CELL_ASPECT_RATIO),
+            max_radius,
+            pts,
+            options,
+        }
+    }
+
+    pub fn with_options_screen<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+        f: Function<X, Y, F>,
+        options: PolarOptions,
+    ) -> PolarGraph {
+        let (w_screen, h_screen) = console_engine::crossterm::terminal::size().unwrap();
+        PolarGraph::with_options(
+            f,
+            w_screen as u32,
+            Some((h_screen as u32).saturating_sub(1)),
+            options,
+        )
+    }
+
+    pub fn draw(&self) {
+        draw_screen(self.render());
+    }
+
+    pub(crate) fn render(&self) -> Screen {
+        let mut scr = Screen::new(self.width, self.height);
+        // Nothing fits, and printing labels on a screen without columns divides by zero
+        if self.width == 0 || self.height == 0 {
+            return scr;
+        }
+
+        if self.options.grid {
+            self.draw_grid(&mut scr);
+        }
+        self.draw_function(&mut scr);
+        self.options.theme.paint_background(&mut scr);
+
+        scr
+    }
+
+    /// Screen cell of the point at radius `r` (in function units) and angle `theta`.
+    fn cell(&self, r: f64, theta: f64) -> (i32, i32) {
+        let scaled = r / self.max_radius * self.radius;
+        (
+            (self.width.saturating_sub(1) as f64 / 2f64 + scaled * theta.cos() * CELL_ASPECT_RATIO)
+                .round() as i32,
+            (self.height.saturating_sub(1) as f64 / 2f64 - scaled * theta.sin()).round() as i32,
+        )
+    }
+
+    fn draw_grid(&self, scr: &mut Screen) {
+        let theme = &self.options.theme;
+        let grid = pixel::pxl_fg('·', theme.grid_color.into());
+        // Spokes
+        for k in 0..self.options.spokes {
+            let theta = k as f64 * TAU / self.options.spokes as f64;
+            let (x, y) = self.cell(self.max_radius, theta);
+            let (cx, cy) = self.cell(0f64, 0f64);
+            scr.line(cx, cy, x, y, grid);
+        }
+        // Rings, each labelled with its radius right before it crosses θ = 0
+        let samples = (self.radius * CELL_ASPECT_RATIO * 8f64).ceil() as u32;
+        for k in 1..=self.options.rings {
+            let r = k as f64 * self.max_radius / self.options.rings as f64;
+            for i in 0..samples {
+                let (x, y) = self.cell(r, i as f64 * TAU / samples as f64);
+                scr.set_pxl(x, y, grid);
+            }
+            let (x, y) = self.cell(r, 0f64);
+            let label = format_value(r);
+            theme.print_legend(scr, x + 1 - label.chars().count() as i32, y, &label);
+        }
+        let (cx, cy) = self.cell(0f64, 0f64);
+        scr.set_pxl(cx, cy, pixel::pxl_fg('+', theme.axis.into()));
+    }
+
+    fn draw_function(&self, scr: &mut Screen) {
+        for (r, theta) in self.pts.iter() {
+            let (x, y) = self.cell(*r, *theta);
+            scr.set_pxl(
+                x,
+                y,
+                pixel::pxl_fg(
+                    self.options.graph.character.as_char(),
+                    self.options.graph.color.into(),
+                ),
+            );
+        }
+    }
+}
+
+impl fmt::Display for PolarGraph {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiny_sizes() {
+        for (width, height) in [
+            (0, Some(0)),
+            (1, Some(1)),
+            (0, None),
+            (1, None),
+            (40, Some(0)),
+        ] {
+            let graph = PolarGraph::new(Function::new(|t: f64| t), width, height);
+            assert_eq!(graph.radius, 0f64);
+            graph.render();
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use derivative::Derivative;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::function::Function;
+use crate::traits::AsF64;
+use crate::types::Scales;
+
+/// Pieces each column is split in before refining, so that spikes narrower than a column are still hit.
+const COLUMN_SUBDIVISIONS: u32 = 4;
+/// Times a piece of a column can be halved, at most `COLUMN_SUBDIVISIONS * 2^MAX_DEPTH` pieces per column.
+const MAX_DEPTH: u32 = 4;
+/// Rows the midpoint of a piece can be away from the straight line between its ends without halving it.
+const CURVATURE_TOLERANCE: f64 = 0.5;
+/// Rows the function can change within a piece without halving it.
+const CHANGE_TOLERANCE: f64 = 1f64;
+
+/// How functions are evaluated to be drawn.
+#[derive(Copy, Clone, Derivative, Debug, PartialEq, Eq)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Sampling {
+    #[derivative(Default)]
+    /// **(Default)** Once per column
+    Uniform,
+    /// Many times per column, more where the function curves or changes fast, drawing the whole range of values the function takes in each column
+    Adaptive,
+}
+
+/// Value of a function at a column, and the smallest and largest values it takes within the column.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColumnRange {
+    pub x: f64,
+    pub y: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl<X: AsF64, Y: AsF64, F: Fn(X) -> Y> Function<X, Y, F> {
+    /// Ranges of the function in columns `x_i` to `x_f`, each column covering half a column at each side of it, in the same units as [`Function::rng_x_scale`].
+    ///
+    /// ```
+    /// use tgraph::{func, Scales};
+    ///
+    /// // A spike between two columns, missed when sampling once per column
+    /// let f = func!(|x| if (x - 2.1f64).abs() < 0.05 { 10f64 } else { 0f64 });
+    /// let ranges = f.rng_adaptive(0, 4, &Scales::default());
+    /// assert_eq!(ranges[2].max, 10f64);
+    /// assert_eq!(ranges[2].y, 0f64);
+    /// ```
+    pub fn rng_adaptive(&self, x_i: u32, x_f: u32, scales: &Scales) -> Vec<ColumnRange> {
+        (x_i..=x_f)
+            .map(|x| self.column_range(x, (x_i, x_f), scales))
+            .collect()
+    }
+
+    /// Range of the function in column `x`, not going past the columns `x_i` and `x_f` at the ends.
+    fn column_range(&self, x: u32, (x_i, x_f): (u32, u32), scales: &Scales) -> ColumnRange {
+        let f = |column: f64| self.at(column * scales.x) * scales.y;
+        let y = f(x as f64);
+        let (start, end) = (
+            (x as f64 - 0.5).max(x_i as f64),
+            (x as f64 + 0.5).min(x_f as f64),
+        );
+        let (mut min, mut max) = if y.is_finite() {
+            (y, y)
+        } else {
+            (f64::NAN, f64::NAN)
+        };
+        let step = (end - start) / COLUMN_SUBDIVISIONS as f64;
+        let mut previous = (start, f(start));
+        for i in 1..=COLUMN_SUBDIVISIONS {
+            let current = (start + i as f64 * step, f(start + i as f64 * step));
+            refine(&f, previous, current, MAX_DEPTH, &mut min, &mut max);
+            previous = current;
+        }
+        ColumnRange {
+            x: x as f64 * scales.x,
+            y,
+            min,
+            max,
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<X: AsF64 + Sync, Y: AsF64 + Sync, F: Fn(X) -> Y + Sync> Function<X, Y, F> {
+    /// Same as [`Function::rng_x`], evaluating the columns in parallel.
+    pub fn par_rng_x(&self, x_i: u32, x_f: u32) -> Vec<(u32, f64)> {
+        (x_i..=x_f)
+            .into_par_iter()
+            .map(|x| (x, self.at(x as f64)))
+            .collect()
+    }
+
+    /// Same as [`Function::rng_x_scale`], evaluating the columns in parallel.
+    pub fn par_rng_x_scale(&self, x_i: u32, x_f: u32, scales: &Scales) -> Vec<(f64, f64)> {
+        (x_i..=x_f)
+            .into_par_iter()
+            .map(|x| {
+                let x = x as f64 * scales.x;
+                (x, self.at(x) * scales.y)
+            })
+            .collect()
+    }
+
+    /// Same as [`Function::rng_adaptive`], evaluating the columns in parallel.
+    pub fn par_rng_adaptive(&self, x_i: u32, x_f: u32, scales: &Scales) -> Vec<ColumnRange> {
+        (x_i..=x_f)
+            .into_par_iter()
+            .map(|x| self.column_range(x, (x_i, x_f), scales))
+            .collect()
+    }
+}
+
+/// Adds the values of `f` at the ends of the piece from `a` to `b` to the range, halving the piece while it curves or changes too much.
+fn refine<F: Fn(f64) -> f64>(
+    f: &F,
+    (a, fa): (f64, f64),
+    (b, fb): (f64, f64),
+    depth: u32,
+    min: &mut f64,
+    max: &mut f64,
+) {
+    add(fa, min, max);
+    add(fb, min, max);
+    if depth == 0 {
+        return;
+    }
+    let m = (a + b) / 2f64;
+    let fm = f(m);
+    if (fm - (fa + fb) / 2f64).abs() > CURVATURE_TOLERANCE || (fb - fa).abs() > CHANGE_TOLERANCE {
+        refine(f, (a, fa), (m, fm), depth - 1, min, max);
+        refine(f, (m, fm), (b, fb), depth - 1, min, max);
+    } else {
+        add(fm, min, max);
+    }
+}
+
+/// Widens the range to include `v`, unless it is infinite or NaN. The range is NaN until a value is added.
+fn add(v: f64, min: &mut f64, max: &mut f64) {
+    if v.is_finite() {
+        *min = min.min(v);
+        *max = max.max(v);
+    }
+}
@@ -0,0 +1,131 @@
+use derivative::Derivative;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::bar_chart::format_value;
+use crate::function::Function;
+use crate::traits::{AsF64, MaybeAsF64};
+
+/// Block characters for each level of a sparkline, from lowest to highest.
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Derivative, TypedBuilder, Clone, Debug)]
+#[derivative(Default)]
+pub struct SparklineOptions {
+    /// Append the minimum and maximum values after the sparkline, e.g. `▂▃▅▇▆▃▂ ↓0.2 ↑7`.
+    #[builder(default)]
+    pub markers: bool,
+}
+
+/// One-line chart made of block characters, meant to be embedded in log lines or table cells.
+///
+/// Unlike other charts it doesn't draw to the terminal, it is rendered to a `String` with [`Sparkline::render`] or `to_string()`.
+///
+/// ```
+/// use tgraph::Sparkline;
+///
+/// let line = Sparkline::new(&[1, 2, 3, 4, 5, 6, 7, 8], 8).to_string();
+/// assert_eq!(line, "▁▂▃▄▅▆▇█");
+/// ```
+pub struct Sparkline {
+    values: Vec<Option<f64>>,
+    options: SparklineOptions,
+}
+
+impl Sparkline {
+    /// `values` are resampled to `width` characters, averaging them when there are more values than characters. Missing values (`None`) are left blank.
+    pub fn new<I: IntoIterator<Item = V>, V: MaybeAsF64>(values: I, width: u32) -> Sparkline {
+        Sparkline::with_options(values, width, SparklineOptions::default())
+    }
+
+    pub fn with_options<I: IntoIterator<Item = V>, V: MaybeAsF64>(
+        values: I,
+        width: u32,
+        options: SparklineOptions,
+    ) -> Sparkline {
+        let values: Vec<Option<f64>> = values
+            .into_iter()
+            .map(|v| v.maybe_as_f64().filter(|v| v.is_finite()))
+            .collect();
+        Sparkline {
+            values: resample(&values, width as usize),
+            options,
+        }
+    }
+
+    /// Samples `f` at `width` evenly spaced points from `x_i` to `x_f`, both included.
+    pub fn function<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+        f: Function<X, Y, F>,
+        x_i: f64,
+        x_f: f64,
+        width: u32,
+    ) -> Sparkline {
+        Sparkline::function_with_options(f, x_i, x_f, width, SparklineOptions::default())
+    }
+
+    pub fn function_with_options<X: AsF64, Y: AsF64, F: Fn(X) -> Y>(
+        f: Function<X, Y, F>,
+        x_i: f64,
+        x_f: f64,
+        width: u32,
+        options: SparklineOptions,
+    ) -> Sparkline {
+        let step = if width > 1 {
+            (x_f - x_i) / (width - 1) as f64
+        } else {
+            0f64
+        };
+        Sparkline::with_options(
+            (0..width).map(|i| Some(f.at(x_i + i as f64 * step)).filter(|y| y.is_finite())),
+            width,
+            options,
+        )
+    }
+
+    pub fn render(&self) -> String {
+        let present = self.values.iter().flatten().copied();
+        let min = present.clone().fold(f64::INFINITY, f64::min);
+        let max = present.fold(f64::NEG_INFINITY, f64::max);
+
+        let mut line: String = self
+            .values
+            .iter()
+            .map(|v| match v {
+                Some(v) if max > min => {
+                    LEVELS[((v - min) / (max - min) * (LEVELS.len() - 1) as f64).round() as usize]
+                }
+                Some(_) => LEVELS[0],
+                None => ' ',
+            })
+            .collect();
+        if self.options.markers && min <= max {
+            line.push_str(&format!(" ↓{} ↑{}", format_value(min), format_value(max)));
+        }
+        line
+    }
+}
+
+impl fmt::Display for Sparkline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(&self.render())
+    }
+}
+
+/// Fits `values` into `width` slots, averaging the values falling in the same slot and repeating them when there are fewer values than slots.
+fn resample(values: &[Option<f64>], width: usize) -> Vec<Option<f64>> {
+    if values.is_empty() {
+        return vec![None; width];
+    }
+    (0..width)
+        .map(|i| {
+            let start = i * values.len() / width;
+            let end = ((i + 1) * values.len() / width).max(start + 1);
+            let present: Vec<f64> = values[start..end].iter().flatten().copied().collect();
+            if present.is_empty() {
+                None
+            } else {
+                Some(present.iter().sum::<f64>() / present.len() as f64)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,277 @@
+use console_engine::crossterm::{cursor, execute};
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use derivative::Derivative;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{stdout, IsTerminal};
+use std::time::{Duration, Instant};
+use typed_builder::TypedBuilder;
+
+use crate::bar_chart::format_value;
+use crate::color::draw_screen;
+use crate::graph::GraphOptions;
+use crate::plane::Plane;
+use crate::theme::Theme;
+use crate::traits::AsF64;
+use crate::types::Domain;
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct StreamingOptions {
+    /// Color and character of the line.
+    #[builder(default)]
+    pub graph: GraphOptions,
+    /// Maximum number of redraws per second, values pushed in between are drawn on the next one.
+    #[derivative(Default(value = "10"))]
+    #[builder(default = 10)]
+    pub refresh_rate: u32,
+    /// Range of values shown. From the minimum to the maximum value in the buffer if `None`.
+    #[builder(default, setter(strip_option))]
+    pub y_domain: Option<(f64, f64)>,
+    /// Colors of the axis, legends, background and grid.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+/// Chart of the last values of a metric, redrawn in place as new values are pushed, the newest one on the right.
+///
+/// Values are kept in a ring buffer of fixed capacity, so the oldest value is dropped when a new one is pushed to a full buffer.
+pub struct StreamingChart {
+    values: VecDeque<f64>,
+    capacity: usize,
+    width: u32,
+    height: u32,
+    options: StreamingOptions,
+    last_draw: Option<Instant>,
+    /// Whether the values changed since the last draw.
+    pending: bool,
+}
+
+impl StreamingChart {
+    /// `height` is the height of the plotting area, by default a quarter of `width`.
+    pub fn new(capacity: usize, width: u32, height: Option<u32>) -> StreamingChart {
+        StreamingChart::with_options(capacity, width, height, StreamingOptions::default())
+    }
+
+    pub fn new_screen(capacity: usize) -> StreamingChart {
+        StreamingChart::with_options_screen(capacity, StreamingOptions::default())
+    }
+
+    pub fn with_options(
+        capacity: usize,
+        width: u32,
+        height: Option<u32>,
+        options: StreamingOptions,
+    ) -> StreamingChart {
+        let capacity = capacity.max(1);
+        StreamingChart {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+            width,
+            height: height.unwrap_or(width / 4),
+            options,
+            last_draw: None,
+            pending: false,
+        }
+    }
+
+    pub fn with_options_screen(capacity: usize, options: StreamingOptions) -> StreamingChart {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        StreamingChart::with_options(capacity, w_screen as u32, None, options)
+    }
+
+    /// Adds a value, and redraws the chart if the last draw is older than the refresh rate allows.
+    pub fn push<V: AsF64>(&mut self, value: V) {
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value.as_f64());
+        self.pending = true;
+
+        let interval = Duration::from_secs(1) / self.options.refresh_rate.max(1);
+        if self.last_draw.map_or(true, |t| t.elapsed() >= interval) {
+            self.draw();
+        }
+    }
+
+    /// Draws the values pushed since the last draw, if any.
+    pub fn flush(&mut self) {
+        if self.pending {
+            self.draw();
+        }
+    }
+
+    /// Values in the buffer, from the oldest to the newest.
+    pub fn values(&self) -> impl Iterator<Item = &f64> {
+        self.values.iter()
+    }
+
+    /// Draws the chart over the previous one, or below the cursor the first time. When the output isn't a terminal, it is drawn below the previous one.
+    pub fn draw(&mut self) {
+        if self.last_draw.is_some() {
+            if stdout().is_terminal() {
+                // The cursor is left at the end of the legend row, right below the plotting area
+                let rows = self.height.max(1) as u16;
+                // If the cursor can't be moved, the chart is drawn below the previous one instead
+                let _ = execute!(stdout(), cursor::MoveUp(rows), cursor::MoveToColumn(0));
+            } else {
+                // Pipes and files can't be drawn over, each draw follows the previous one
+                println!();
+            }
+        }
+        draw_screen(self.render());
+        self.last_draw = Some(Instant::now());
+        self.pending = false;
+    }
+
+    fn render(&self) -> Screen {
+        let plane = Plane::new(self.domain(), self.width, Some(self.height));
+        let mut scr = Screen::new(plane.widths.total, plane.height);
+
+        plane.draw_axis(&mut scr, &self.options.theme);
+        if self.options.graph.height_legend {
+            plane.draw_y_legend(&mut scr, &self.options.theme);
+        }
+        self.draw_values(&plane, &mut scr);
+        // Newest value under the right end of the chart
+        if let Some(last) = self.values.back() {
+            let label = format_value(*last);
+            self.options.theme.print_legend(
+                &mut scr,
+                plane.widths.total as i32 - label.chars().count() as i32,
+                plane.graph_height as i32 + 1,
+                &label,
+            );
+        }
+        self.options.theme.paint_background(&mut scr);
+
+        scr
+    }
+
+    fn domain(&self) -> Domain {
+        let y = self.options.y_domain.unwrap_or_else(|| {
+            let (min, max) = self
+                .values
+                .iter()
+                .filter(|v| v.is_finite())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                    (min.min(*v), max.max(*v))
+                });
+            if min > max {
+                (-1f64, 1f64)
+            } else if min == max {
+                (min - 1f64, max + 1f64)
+            } else {
+                (min, max)
+            }
+        });
+        // Positions counted from 1, so that no axis is drawn at the left border
+        Domain {
+            x: (1f64, self.capacity.max(2) as f64),
+            y,
+        }
+    }
+
+    fn draw_values(&self, plane: &Plane, scr: &mut Screen) {
+        let pxl = pixel::pxl_fg(
+            self.options.graph.character.as_char(),
+            self.options.graph.color.into(),
+        );
+        let offset = self.capacity - self.values.len();
+        let mut previous: Option<(i32, i32)> = None;
+        for (i, v) in self.values.iter().enumerate() {
+            let current = plane.screen((offset + i + 1) as f64, *v);
+            match (previous, current) {
+                (Some((x0, y0)), Some((x1, y1))) => scr.line(x0, y0, x1, y1, pxl),
+                (None, Some((x, y))) => scr.set_pxl(x, y, pxl),
+                _ => {}
+            }
+            previous = current;
+        }
+    }
+}
+
+impl fmt::Display for StreamingChart {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        draw_screen(self.render());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::plain_text;
+
+    /// Columns of the line on each row of the plotting area.
+    fn line_columns(chart: &StreamingChart) -> Vec<Vec<usize>> {
+        let marker = chart.options.graph.character.as_char();
+        plain_text(&chart.render())
+            .lines()
+            .take(chart.height as usize)
+            .map(|row| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == marker)
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn oldest_values_are_evicted() {
+        let mut chart = StreamingChart::new(3, 40, Some(4));
+        for v in 1..=5 {
+            chart.push(v);
+        }
+        assert_eq!(
+            chart.values().copied().collect::<Vec<_>>(),
+            vec![3f64, 4f64, 5f64]
+        );
+    }
+
+    #[test]
+    fn window_shows_the_buffer_on_the_right() {
+        let options = || StreamingOptions::builder().y_domain((0f64, 3f64)).build();
+        let mut chart = StreamingChart::with_options(4, 40, Some(4), options());
+        chart.push(0);
+        chart.push(0);
+        // Half of the buffer, drawn on the right half
+        let rows = line_columns(&chart);
+        assert!(rows[..3].iter().all(|r| r.is_empty()));
+        assert!(rows[3][0] > 20);
+        assert_eq!(*rows[3].last().unwrap(), 39);
+        // The last row ends with the legend of the newest value
+        assert!(plain_text(&chart.render())
+            .lines()
+            .nth(4)
+            .unwrap()
+            .ends_with('0'));
+
+        let mut chart = StreamingChart::with_options(4, 40, Some(4), options());
+        for v in [3, 0, 0, 0, 0] {
+            chart.push(v);
+        }
+        // The 3 is evicted, the full buffer is a flat line across the chart
+        let rows = line_columns(&chart);
+        assert!(rows[..3].iter().all(|r| r.is_empty()));
+        assert!(rows[3][0] < 5);
+    }
+
+    #[test]
+    fn draws_are_throttled() {
+        let options = StreamingOptions::builder().refresh_rate(1).build();
+        let mut chart = StreamingChart::with_options(10, 40, Some(4), options);
+        chart.push(1);
+        assert!(chart.last_draw.is_some() && !chart.pending);
+        let first = chart.last_draw;
+        chart.push(2);
+        assert!(chart.pending);
+        assert_eq!(chart.last_draw, first);
+        chart.flush();
+        assert!(!chart.pending);
+        assert_ne!(chart.last_draw, first);
+    }
+}
@@ -0,0 +1,255 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use console_engine::Color;
+use derivative::Derivative;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::color::ColorSupport;
+use crate::graph::GraphOptions;
+use crate::types::ColorWrapper;
+
+/// Rows between the horizontal lines of a grid, counted up from the x axis.
+const GRID_ROWS: u32 = 5;
+/// Columns between the vertical lines of a grid, counted right from the y axis.
+const GRID_COLUMNS: u32 = 10;
+
+/// Colors and grid shared by all the parts of a chart.
+///
+/// ```
+/// use tgraph::{Color, Theme};
+///
+/// let theme = Theme::solarized();
+/// assert_eq!(theme.series().len(), theme.palette.len());
+/// let custom = Theme::builder().axis(Color::DarkGrey.into()).build();
+/// assert_eq!(custom.palette.len(), Theme::default().palette.len());
+/// ```
+#[derive(Derivative, TypedBuilder, Clone, Debug)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Theme {
+    /// Colors given to the series of a chart, in order, starting over after the last one.
+    #[derivative(Default(value = "default_palette()"))]
+    #[builder(default = default_palette())]
+    pub palette: Vec<ColorWrapper>,
+    /// Color of the axis lines.
+    #[derivative(Default(value = "Color::Reset.into()"))]
+    #[builder(default = Color::Reset.into())]
+    pub axis: ColorWrapper,
+    /// Color of the numbers in the legends.
+    #[derivative(Default(value = "Color::Reset.into()"))]
+    #[builder(default = Color::Reset.into())]
+    pub legend: ColorWrapper,
+    /// Color behind the chart, the one of the terminal if `None`.
+    #[builder(default, setter(strip_option))]
+    pub background: Option<ColorWrapper>,
+    #[builder(default)]
+    pub grid: GridStyle,
+    /// Color of the grid, of the lines through the origin of charts over the plane and of the rings and spokes of polar charts.
+    #[derivative(Default(value = "Color::DarkGrey.into()"))]
+    #[builder(default = Color::DarkGrey.into())]
+    pub grid_color: ColorWrapper,
+}
+
+/// Lines drawn across the plotting area, every few rows and columns.
+#[derive(Copy, Clone, Derivative, Debug, PartialEq, Eq)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GridStyle {
+    #[derivative(Default)]
+    /// **(Default)** No grid
+    None,
+    /// Dotted lines (`┈`, `┊`)
+    Dotted,
+    /// Dashed lines (`╌`, `╎`)
+    Dashed,
+    /// Solid lines (`─`, `│`)
+    Solid,
+}
+
+impl GridStyle {
+    /// Characters of the horizontal lines, the vertical lines and their crossings.
+    fn characters(&self) -> Option<(char, char, char)> {
+        match self {
+            GridStyle::None => None,
+            GridStyle::Dotted => Some(('┈', '┊', '┼')),
+            GridStyle::Dashed => Some(('╌', '╎', '┼')),
+            GridStyle::Solid => Some(('─', '│', '┼')),
+        }
+    }
+}
+
+fn default_palette() -> Vec<ColorWrapper> {
+    [
+        Color::Red,
+        Color::Blue,
+        Color::Green,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Yellow,
+        Color::DarkRed,
+        Color::DarkBlue,
+        Color::DarkGreen,
+        Color::DarkMagenta,
+        Color::DarkCyan,
+        Color::DarkYellow,
+    ]
+    .into_iter()
+    .map(ColorWrapper::from)
+    .collect()
+}
+
+/// Colors given as `0xrrggbb`.
+fn rgb_palette(colors: &[u32]) -> Vec<ColorWrapper> {
+    colors.iter().map(|c| rgb(*c)).collect()
+}
+
+fn rgb(c: u32) -> ColorWrapper {
+    Color::Rgb {
+        r: (c >> 16) as u8,
+        g: (c >> 8) as u8,
+        b: c as u8,
+    }
+    .into()
+}
+
+impl Theme {
+    /// No colors at all, everything in the color of the terminal text.
+    pub fn monochrome() -> Theme {
+        Theme {
+            palette: vec![Color::Reset.into()],
+            grid_color: Color::Reset.into(),
+            ..Theme::default()
+        }
+    }
+
+    /// Okabe and Ito's palette, told apart with any kind of color blindness.
+    pub fn colorblind() -> Theme {
+        Theme {
+            palette: rgb_palette(&[
+                0xe69f00, 0x56b4e9, 0x009e73, 0xf0e442, 0x0072b2, 0xd55e00, 0xcc79a7, 0x999999,
+            ]),
+            ..Theme::default()
+        }
+    }
+
+    /// Ethan Schoonover's Solarized (dark) palette, on its own background.
+    pub fn solarized() -> Theme {
+        Theme {
+            palette: rgb_palette(&[
+                0xb58900, 0xcb4b16, 0xdc322f, 0xd33682, 0x6c71c4, 0x268bd2, 0x2aa198, 0x859900,
+            ]),
+            axis: rgb(0x586e75),
+            legend: rgb(0x93a1a1),
+            background: Some(rgb(0x002b36)),
+            grid: GridStyle::Dotted,
+            grid_color: rgb(0x586e75),
+        }
+    }
+
+    /// Bright colors on black, for low-quality screens and projectors.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            palette: [
+                Color::Yellow,
+                Color::Cyan,
+                Color::Magenta,
+                Color::Green,
+                Color::Red,
+                Color::White,
+            ]
+            .into_iter()
+            .map(ColorWrapper::from)
+            .collect(),
+            axis: Color::White.into(),
+            legend: Color::White.into(),
+            background: Some(Color::Black.into()),
+            grid: GridStyle::Solid,
+            grid_color: Color::White.into(),
+        }
+    }
+
+    /// Default theme with `n` series colors evenly spaced from `from` to `to`, so that series are told apart by their order.
+    pub fn gradient(from: ColorWrapper, to: ColorWrapper, n: usize) -> Theme {
+        Theme {
+            palette: ColorWrapper::gradient(from, to, n),
+            ..Theme::default()
+        }
+    }
+
+    /// Color of the `i`-th series.
+    pub fn color(&self, i: usize) -> ColorWrapper {
+        self.palette
+            .get(i % self.palette.len().max(1))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Options of a series in each color of the palette.
+    pub fn series(&self) -> Vec<GraphOptions> {
+        (0..self.palette.len())
+            .map(|i| GraphOptions {
+                color: self.color(i),
+                ..GraphOptions::default()
+            })
+            .collect()
+    }
+
+    /// Adds default options to `series` up to `n` series, in the colors of the palette at their positions.
+    pub(crate) fn pad_series(&self, series: &mut Vec<GraphOptions>, n: usize) {
+        for i in series.len()..n {
+            series.push(GraphOptions {
+                color: self.color(i),
+                ..GraphOptions::default()
+            });
+        }
+    }
+
+    /// Prints a label of a legend.
+    pub(crate) fn print_legend(&self, scr: &mut Screen, x: i32, y: i32, label: &str) {
+        scr.print_fbg(x, y, label, self.legend.into(), Color::Reset);
+    }
+
+    /// Draws the grid lines on the blank cells of the plotting area, whose bottom left corner is the crossing of the axes at (`left`, `bottom`).
+    pub(crate) fn draw_grid(&self, scr: &mut Screen, (left, bottom): (i32, i32), right: i32) {
+        let (horizontal, vertical, crossing) = match self.grid.characters() {
+            Some(characters) => characters,
+            None => return,
+        };
+        for y in 0..bottom {
+            for x in left + 1..=right {
+                let on_row = (bottom - y) as u32 % GRID_ROWS == 0;
+                let on_column = (x - left) as u32 % GRID_COLUMNS == 0;
+                let chr = match (on_row, on_column) {
+                    (true, true) => crossing,
+                    (true, false) => horizontal,
+                    (false, true) => vertical,
+                    (false, false) => continue,
+                };
+                if scr.get_pxl(x, y).is_ok_and(|p| p.chr == ' ') {
+                    scr.set_pxl(x, y, pixel::pxl_fg(chr, self.grid_color.into()));
+                }
+            }
+        }
+    }
+
+    /// Paints the background color behind every cell that doesn't have its own, unless charts are drawn without colors.
+    pub(crate) fn paint_background(&self, scr: &mut Screen) {
+        let background = match self.background {
+            Some(background) if ColorSupport::current() != ColorSupport::None => background.into(),
+            _ => return,
+        };
+        for y in 0..scr.get_height() as i32 {
+            for x in 0..scr.get_width() as i32 {
+                if let Ok(p) = scr.get_pxl(x, y) {
+                    if p.bg == Color::Reset {
+                        scr.set_pxl(x, y, pixel::pxl_fbg(p.chr, p.fg, background));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use derivative::Derivative;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::color::draw_screen;
+use crate::graph::GraphOptions;
+use crate::plane::Plane;
+use crate::theme::Theme;
+use crate::traits::{AsF64, Timestamp};
+use crate::types::Domain;
+
+const MINUTE: f64 = 60f64;
+const HOUR: f64 = 60f64 * MINUTE;
+const DAY: f64 = 24f64 * HOUR;
+
+/// Candidate distances between two labels of the time legend, in seconds.
+const STEPS: [f64; 22] = [
+    1f64,
+    2f64,
+    5f64,
+    10f64,
+    15f64,
+    30f64,
+    MINUTE,
+    2f64 * MINUTE,
+    5f64 * MINUTE,
+    10f64 * MINUTE,
+    15f64 * MINUTE,
+    30f64 * MINUTE,
+    HOUR,
+    2f64 * HOUR,
+    3f64 * HOUR,
+    6f64 * HOUR,
+    12f64 * HOUR,
+    DAY,
+    2f64 * DAY,
+    7f64 * DAY,
+    14f64 * DAY,
+    30f64 * DAY,
+];
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct TimeSeriesOptions {
+    /// Options for each series, in the same order as the series passed to the chart. Series without options get the default ones, in the color of the theme at their position.
+    #[builder(default)]
+    pub series: Vec<GraphOptions>,
+    /// Time range shown, as Unix timestamps in seconds (see [`Timestamp::unix_seconds`]). From the first to the last point if `None`, or if the range isn't finite or doesn't end after it starts.
+    #[builder(default, setter(strip_option))]
+    pub x_domain: Option<(f64, f64)>,
+    /// Range of values shown. From the minimum to the maximum value if `None`, or if the range isn't finite or doesn't end after it starts.
+    #[builder(default, setter(strip_option))]
+    pub y_domain: Option<(f64, f64)>,
+    /// Join consecutive points of a series with a line.
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub lines: bool,
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub height_legend: bool,
+    /// Colors of the axis, legends, background and grid, and of the series without options.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+/// Chart of series of `(time, value)` points, with a time legend (in UTC) whose granularity (seconds, minutes, hours or days) depends on the range shown.
+pub struct TimeSeries {
+    plane: Plane,
+    /// Points of each series as (Unix seconds, value), sorted by time.
+    series: Vec<Vec<(f64, f64)>>,
+    options: TimeSeriesOptions,
+}
+
+impl TimeSeries {
+    /// `set_height` is the height of the plotting area, by default a quarter of `width`.
+    pub fn new<T: Timestamp, V: AsF64>(
+        series: Vec<Vec<(T, V)>>,
+        width: u32,
+        set_height: Option<u32>,
+    ) -> TimeSeries {
+        TimeSeries::with_options(series, width, set_height, TimeSeriesOptions::default())
+    }
+
+    pub fn new_screen<T: Timestamp, V: AsF64>(series: Vec<Vec<(T, V)>>) -> TimeSeries {
+        TimeSeries::with_options_screen(series, TimeSeriesOptions::default())
+    }
+
+    pub fn with_options<T: Timestamp, V: AsF64>(
+        series: Vec<Vec<(T, V)>>,
+        width: u32,
+        set_height: Option<u32>,
+        mut options: TimeSeriesOptions,
+    ) -> TimeSeries {
+        options.theme.pad_series(&mut options.series, series.len());
+        let series: Vec<Vec<(f64, f64)>> = series
+            .into_iter()
+            .map(|s| {
+                let mut pts: Vec<(f64, f64)> = s
+                    .into_iter()
+                    .map(|(t, v)| (t.unix_seconds(), v.as_f64()))
+                    .filter(|(t, v)| t.is_finite() && v.is_finite())
+                    .collect();
+                pts.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                pts
+            })
+            .collect();
+        let domain = Domain {
+            x: valid(options.x_domain)
+                .unwrap_or_else(|| widen(bounds(series.iter().flatten().map(|(t, _)| *t)), MINUTE)),
+            y: valid(options.y_domain)
+                .unwrap_or_else(|| widen(bounds(series.iter().flatten().map(|(_, v)| *v)), 1f64)),
+        };
+        TimeSeries {
+            plane: Plane::new(domain, width, Some(set_height.unwrap_or(width / 4))),
+            series,
+            options,
+        }
+    }
+
+    pub fn with_options_screen<T: Timestamp, V: AsF64>(
+        series: Vec<Vec<(T, V)>>,
+        options: TimeSeriesOptions,
+    ) -> TimeSeries {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        TimeSeries::with_options(series, w_screen as u32, None, options)
+    }
+
+    pub fn draw(&self) {
+        let mut scr = Screen::new(self.plane.widths.total, self.plane.height);
+
+        self.plane.draw_axis(&mut scr, &self.options.theme);
+        if self.options.height_legend {
+            self.plane.draw_y_legend(&mut scr, &self.options.theme);
+        }
+        self.draw_time_legend(&mut scr);
+        self.draw_series(&mut scr);
+
+        self.options.theme.paint_background(&mut scr);
+
+        draw_screen(scr);
+    }
+
+    fn draw_series(&self, scr: &mut Screen) {
+        for (pts, options) in self.series.iter().zip(self.options.series.iter()) {
+            let pxl = pixel::pxl_fg(options.character.as_char(), options.color.into());
+            let mut previous: Option<(i32, i32)> = None;
+            for (t, v) in pts {
+                let current = self.plane.screen(*t, *v);
+                match (previous, current) {
+                    (Some((x0, y0)), Some((x1, y1))) if self.options.lines => {
+                        scr.line(x0, y0, x1, y1, pxl)
+                    }
+                    (_, Some((x, y))) => scr.set_pxl(x, y, pxl),
+                    _ => {}
+                }
+                previous = current;
+            }
+        }
+    }
+
+    /// Labels at round times, as far apart as needed for them not to overlap.
+    fn draw_time_legend(&self, scr: &mut Screen) {
+        let (start, end) = self.plane.domain.x;
+        let seconds_per_col = self.plane.domain.width() / self.plane.cols().max(1) as f64;
+        let fits = |step: f64| step / seconds_per_col >= (label_width(step) + 2) as f64;
+        let step = match STEPS.iter().find(|s| fits(**s)) {
+            Some(step) => *step,
+            // The smallest whole multiple of the largest step that fits, for very long ranges
+            None => {
+                let largest = STEPS[STEPS.len() - 1];
+                let columns = (label_width(largest) + 2) as f64;
+                (columns * seconds_per_col / largest).ceil().max(1f64) * largest
+            }
+        };
+
+        let legend_row = self.plane.graph_height as i32 + 1;
+        let mut free_from = 0;
+        let mut t = (start / step).ceil() * step;
+        while t <= end {
+            if let Some((x, _)) = self.plane.screen(t, self.plane.domain.y.0) {
+                let label = format_time(t, step);
+                if x >= free_from && x + label.len() as i32 <= self.plane.widths.total as i32 {
+                    self.options.theme.print_legend(scr, x, legend_row, &label);
+                    free_from = x + label.len() as i32 + 1;
+                }
+            }
+            t += step;
+        }
+    }
+}
+
+impl fmt::Display for TimeSeries {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+fn bounds<I: Iterator<Item = f64>>(values: I) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    })
+}
+
+/// `domain` if it is finite and not empty.
+fn valid(domain: Option<(f64, f64)>) -> Option<(f64, f64)> {
+    domain.filter(|(min, max)| min.is_finite() && max.is_finite() && min < max)
+}
+
+/// Range `(min, max)`, grown by `margin` on each side if it is empty, or around 0 if there were no values.
+fn widen((min, max): (f64, f64), margin: f64) -> (f64, f64) {
+    if min > max {
+        (-margin, margin)
+    } else if min == max {
+        (min - margin, max + margin)
+    } else {
+        (min, max)
+    }
+}
+
+/// Width of the labels of a time legend with labels every `step` seconds.
+fn label_width(step: f64) -> usize {
+    if step < MINUTE {
+        "hh:mm:ss".len()
+    } else if step < 30f64 * DAY {
+        "hh:mm".len()
+    } else {
+        "yyyy-mm-dd".len()
+    }
+}
+
+/// Label of Unix time `t` in a time legend with labels every `step` seconds: the time of the day, the date at midnight or for long steps.
+fn format_time(t: f64, step: f64) -> String {
+    let seconds = t.round() as i64;
+    let (days, time) = (
+        seconds.div_euclid(DAY as i64),
+        seconds.rem_euclid(DAY as i64),
+    );
+    let (year, month, day) = civil_from_days(days);
+    if step >= 30f64 * DAY {
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    } else if step >= DAY || time == 0 {
+        format!("{:02}-{:02}", month, day)
+    } else if step >= MINUTE {
+        format!("{:02}:{:02}", time / 3600, time % 3600 / 60)
+    } else {
+        format!(
+            "{:02}:{:02}:{:02}",
+            time / 3600,
+            time % 3600 / 60,
+            time % 60
+        )
+    }
+}
+
+/// Year, month and day of the date `days` days after 1970-01-01, in the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months counted from March, so that the leap day is the last one of the year
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::plain_text;
+
+    fn chart(x_domain: (f64, f64)) -> TimeSeries {
+        let series = vec![vec![(1_700_000_000u64, 1f64), (1_700_003_600u64, 2f64)]];
+        let options = TimeSeriesOptions::builder().x_domain(x_domain).build();
+        TimeSeries::with_options(series, 80, Some(10), options)
+    }
+
+    /// Row of the time labels.
+    fn time_legend(chart: &TimeSeries) -> String {
+        let mut scr = Screen::new(chart.plane.widths.total, chart.plane.height);
+        chart.draw_time_legend(&mut scr);
+        plain_text(&scr)
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    #[test]
+    fn invalid_x_domains_fall_back_to_the_data() {
+        for x_domain in [
+            (1_700_003_600f64, 1_700_000_000f64),
+            (1_700_000_000f64, 1_700_000_000f64),
+            (0f64, f64::INFINITY),
+            (f64::NAN, 1f64),
+        ] {
+            let chart = chart(x_domain);
+            assert_eq!(chart.plane.domain.x, (1_700_000_000f64, 1_700_003_600f64));
+            assert!(!time_legend(&chart).trim().is_empty());
+        }
+    }
+
+    #[test]
+    fn long_ranges_get_labels() {
+        let chart = chart((0f64, 1e12));
+        assert_eq!(chart.plane.domain.x, (0f64, 1e12));
+        assert!(!time_legend(&chart).trim().is_empty());
+    }
+}
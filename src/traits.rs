@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 pub trait AsF64 {
     #[allow(clippy::wrong_self_convention)]
     fn as_f64(self) -> f64;
@@ -61,6 +63,30 @@ where
     }
 }
 
+/// References to plain numbers, so slices and other borrowed collections can be iterated directly.
+macro_rules! impl_maybe_asf64_ref {
+    ($type:ty) => {
+        impl MaybeAsF64 for &$type {
+            fn maybe_as_f64(self) -> Option<f64> {
+                Some((*self).as_f64())
+            }
+        }
+    };
+}
+
+impl_maybe_asf64_ref!(u8);
+impl_maybe_asf64_ref!(u16);
+impl_maybe_asf64_ref!(u32);
+impl_maybe_asf64_ref!(u64);
+impl_maybe_asf64_ref!(u128);
+impl_maybe_asf64_ref!(i8);
+impl_maybe_asf64_ref!(i16);
+impl_maybe_asf64_ref!(i32);
+impl_maybe_asf64_ref!(i64);
+impl_maybe_asf64_ref!(i128);
+impl_maybe_asf64_ref!(f32);
+impl_maybe_asf64_ref!(f64);
+
 impl<T> MaybeAsF64 for Option<T>
 where
     T: AsF64,
@@ -69,3 +95,27 @@ where
         self.map(|v| v.as_f64())
     }
 }
+
+/// Points in time on the x axis of a [`TimeSeries`](crate::TimeSeries): plain numbers are Unix timestamps in seconds.
+pub trait Timestamp {
+    /// Seconds since the Unix epoch, negative before it.
+    fn unix_seconds(self) -> f64;
+}
+
+impl<T> Timestamp for T
+where
+    T: AsF64,
+{
+    fn unix_seconds(self) -> f64 {
+        self.as_f64()
+    }
+}
+
+impl Timestamp for SystemTime {
+    fn unix_seconds(self) -> f64 {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => after.as_secs_f64(),
+            Err(before) => -before.duration().as_secs_f64(),
+        }
+    }
+}
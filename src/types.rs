@@ -1,11 +1,19 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
 use console_engine::Color;
 use derivative::Derivative;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error;
 use std::fmt;
 use std::fmt::Write;
+use std::str::FromStr;
 
 /// Enum used in `tgraph` to represent painting character.
 #[derive(Copy, Clone, Derivative, Debug)]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Character {
     /// Unicode circle with cross character (¤)
     CrossCircle,
@@ -102,8 +110,227 @@ impl From<ColorWrapper> for Color {
     }
 }
 
+/// Names of the colors of the terminal palette, as read and written by [`ColorWrapper`].
+const COLOR_NAMES: [(&str, Color); 17] = [
+    ("reset", Color::Reset),
+    ("black", Color::Black),
+    ("dark_grey", Color::DarkGrey),
+    ("red", Color::Red),
+    ("dark_red", Color::DarkRed),
+    ("green", Color::Green),
+    ("dark_green", Color::DarkGreen),
+    ("yellow", Color::Yellow),
+    ("dark_yellow", Color::DarkYellow),
+    ("blue", Color::Blue),
+    ("dark_blue", Color::DarkBlue),
+    ("magenta", Color::Magenta),
+    ("dark_magenta", Color::DarkMagenta),
+    ("cyan", Color::Cyan),
+    ("dark_cyan", Color::DarkCyan),
+    ("white", Color::White),
+    ("grey", Color::Grey),
+];
+
+impl fmt::Display for ColorWrapper {
+    /// Name of the color (`dark_blue`), `#rrggbb` for RGB colors or `ansi(n)` for colors of the 256-color palette.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.0 {
+            Color::Rgb { r, g, b } => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            Color::AnsiValue(v) => write!(f, "ansi({})", v),
+            color => {
+                let (name, _) = COLOR_NAMES.iter().find(|(_, c)| *c == color).unwrap();
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+/// Color that couldn't be parsed into a [`ColorWrapper`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorError {
+    pub color: String,
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "unknown color `{}`", self.color)
+    }
+}
+
+impl Error for ParseColorError {}
+
+impl FromStr for ColorWrapper {
+    type Err = ParseColorError;
+
+    /// Parses the formats written by `Display`. Names are case insensitive, and may use dashes or spaces instead of underscores and `gray` instead of `grey`.
+    ///
+    /// ```
+    /// use tgraph::{Color, ColorWrapper};
+    ///
+    /// let color: ColorWrapper = "Dark Gray".parse().unwrap();
+    /// assert_eq!(Color::from(color), Color::DarkGrey);
+    /// assert_eq!("#ff8000".parse::<ColorWrapper>().unwrap().to_string(), "#ff8000");
+    /// assert_eq!("ansi(208)".parse::<ColorWrapper>().unwrap().to_string(), "ansi(208)");
+    /// ```
+    fn from_str(s: &str) -> Result<ColorWrapper, ParseColorError> {
+        let error = || ParseColorError {
+            color: s.to_string(),
+        };
+        let name = s
+            .trim()
+            .to_lowercase()
+            .replace(['-', ' '], "_")
+            .replace("gray", "grey");
+        if let Some(hex) = name.strip_prefix('#') {
+            let channel = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|c| u8::from_str_radix(c, 16).ok())
+                    .ok_or_else(error)
+            };
+            if hex.len() != 6 {
+                return Err(error());
+            }
+            return Ok(ColorWrapper(Color::Rgb {
+                r: channel(0)?,
+                g: channel(2)?,
+                b: channel(4)?,
+            }));
+        }
+        if let Some(value) = name.strip_prefix("ansi(").and_then(|v| v.strip_suffix(')')) {
+            return value
+                .parse()
+                .map(|v| ColorWrapper(Color::AnsiValue(v)))
+                .map_err(|_| error());
+        }
+        COLOR_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, c)| ColorWrapper(*c))
+            .ok_or_else(error)
+    }
+}
+
+/// Written as a string in the format of `Display`.
+#[cfg(feature = "serde")]
+impl Serialize for ColorWrapper {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ColorWrapper {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ColorWrapper, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// How the area under a curve (or between two curves) is painted.
+#[derive(Copy, Clone, Derivative, Debug)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Fill {
+    #[derivative(Default)]
+    /// **(Default)** Area is left empty
+    None,
+    /// Paint the area with a character, in the color of the function
+    Character(Character),
+    /// Paint the background of the area with a color
+    Background(ColorWrapper),
+}
+
+impl Fill {
+    /// Paints the cell at (`x`, `y`) as part of the area of a function drawn in `color`. Characters already on the screen (axis, legends) are kept.
+    pub(crate) fn paint(&self, scr: &mut Screen, x: i32, y: i32, color: ColorWrapper) {
+        let current = match scr.get_pxl(x, y) {
+            Ok(current) => current,
+            Err(_) => return,
+        };
+        match self {
+            Fill::None => {}
+            Fill::Character(c) if current.chr == ' ' => {
+                scr.set_pxl(x, y, pixel::pxl_fg(c.as_char(), color.into()))
+            }
+            Fill::Character(_) => {}
+            Fill::Background(bg) => {
+                scr.set_pxl(x, y, pixel::pxl_fbg(current.chr, current.fg, (*bg).into()))
+            }
+        }
+    }
+}
+
+/// Columns of the line samples of legends.
+pub(crate) const LINE_SAMPLE_WIDTH: usize = 5;
+
+/// Columns a function is drawn on, so that series of the same color can be told apart.
+#[derive(Copy, Clone, Derivative, Debug, PartialEq, Eq)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LineStyle {
+    #[derivative(Default)]
+    /// **(Default)** Every column
+    Solid,
+    /// Three columns out of every four
+    Dashed,
+    /// Every other column
+    Dotted,
+}
+
+impl LineStyle {
+    /// Whether the function is drawn on the `x`-th column of the plotting area.
+    pub fn draws(&self, x: u32) -> bool {
+        match self {
+            LineStyle::Solid => true,
+            LineStyle::Dashed => x % 4 != 3,
+            LineStyle::Dotted => x % 2 == 0,
+        }
+    }
+
+    /// Short stretch of a line in this style drawn with `c`, as shown in legends.
+    ///
+    /// ```
+    /// use tgraph::LineStyle;
+    ///
+    /// assert_eq!(LineStyle::Dashed.sample('•'), "••• •");
+    /// ```
+    pub fn sample(&self, c: char) -> String {
+        (0..LINE_SAMPLE_WIDTH as u32)
+            .map(|x| if self.draws(x) { c } else { ' ' })
+            .collect()
+    }
+}
+
+/// Rectangular region of the plane, as `(min, max)` ranges of x and y.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Domain {
+    pub x: (f64, f64),
+    pub y: (f64, f64),
+}
+
+impl Domain {
+    pub fn width(&self) -> f64 {
+        self.x.1 - self.x.0
+    }
+
+    pub fn height(&self) -> f64 {
+        self.y.1 - self.y.0
+    }
+}
+
+impl From<((f64, f64), (f64, f64))> for Domain {
+    fn from((x, y): ((f64, f64), (f64, f64))) -> Domain {
+        Domain { x, y }
+    }
+}
+
 #[derive(Derivative)]
 #[derivative(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Scales {
     #[derivative(Default(value = "1f64"))]
     pub x: f64,
@@ -0,0 +1,269 @@
+use console_engine::pixel;
+use console_engine::screen::Screen;
+use derivative::Derivative;
+use std::f64::consts::PI;
+use std::fmt;
+use typed_builder::TypedBuilder;
+
+use crate::color::draw_screen;
+use crate::parametric::CELL_ASPECT_RATIO;
+use crate::plane::Plane;
+use crate::theme::Theme;
+use crate::traits::AsF64;
+use crate::types::{ColorWrapper, Domain};
+
+/// Arrows for each eighth of a turn, counterclockwise from pointing right.
+const ARROWS: [char; 8] = ['→', '↗', '↑', '↖', '←', '↙', '↓', '↘'];
+/// Drawn where the field is zero.
+const NULL: char = '·';
+
+#[derive(Derivative, TypedBuilder, Debug)]
+#[derivative(Default)]
+pub struct VectorFieldOptions {
+    /// Columns and rows between two arrows.
+    #[derivative(Default(value = "(4, 2)"))]
+    #[builder(default = (4, 2))]
+    pub spacing: (u32, u32),
+    /// Color of the arrows. With more than one color, arrows are colored by the magnitude of the vector, from the smallest to the largest.
+    #[derivative(Default(value = "vec![ColorWrapper::default()]"))]
+    #[builder(default = vec![ColorWrapper::default()])]
+    pub colors: Vec<ColorWrapper>,
+    #[derivative(Default(value = "true"))]
+    #[builder(default = true)]
+    pub height_legend: bool,
+    /// Colors of the axis, legends, background and grid. The arrows use `colors`.
+    #[builder(default)]
+    pub theme: Theme,
+}
+
+/// Direction field of a function `(x, y) -> (dx, dy)` over a [`Domain`], drawn as arrows on a grid.
+///
+/// For the slope field of an ODE `y' = g(x, y)`, use `|x, y| (1f64, g(x, y))`.
+pub struct VectorField {
+    plane: Plane,
+    /// Screen position, screen angle and magnitude of each arrow.
+    arrows: Vec<((i32, i32), f64, f64)>,
+    max_magnitude: f64,
+    options: VectorFieldOptions,
+}
+
+impl VectorField {
+    /// `set_height` is the height of the plotting area, by default the one keeping the same scale on both axes.
+    pub fn new<U: AsF64, V: AsF64, F: Fn(f64, f64) -> (U, V)>(
+        f: F,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+    ) -> VectorField {
+        VectorField::with_options(f, domain, width, set_height, VectorFieldOptions::default())
+    }
+
+    pub fn new_screen<U: AsF64, V: AsF64, F: Fn(f64, f64) -> (U, V)>(
+        f: F,
+        domain: Domain,
+    ) -> VectorField {
+        VectorField::with_options_screen(f, domain, VectorFieldOptions::default())
+    }
+
+    pub fn with_options<U: AsF64, V: AsF64, F: Fn(f64, f64) -> (U, V)>(
+        f: F,
+        domain: Domain,
+        width: u32,
+        set_height: Option<u32>,
+        options: VectorFieldOptions,
+    ) -> VectorField {
+        let plane = Plane::new(domain, width, set_height);
+        let (step_x, step_y) = (options.spacing.0.max(1), options.spacing.1.max(1));
+        // Size of a unit of the domain on the screen, rows are taller than columns are wide
+        let (scale_x, scale_y) = (
+            plane.cols() as f64 / domain.width(),
+            plane.rows() as f64 / domain.height() * CELL_ASPECT_RATIO,
+        );
+        let mut arrows = Vec::new();
+        for row in (step_y / 2..plane.rows()).step_by(step_y as usize) {
+            for col in (step_x / 2..plane.cols()).step_by(step_x as usize) {
+                let (x, y) = plane.center(col, row);
+                let (dx, dy) = f(x, y);
+                let (dx, dy) = (dx.as_f64(), dy.as_f64());
+                if !dx.is_finite() || !dy.is_finite() {
+                    continue;
+                }
+                if let Some(position) = plane.screen(x, y) {
+                    arrows.push((position, (dy * scale_y).atan2(dx * scale_x), dx.hypot(dy)));
+                }
+            }
+        }
+        VectorField {
+            plane,
+            max_magnitude: arrows.iter().map(|(_, _, m)| *m).fold(0f64, f64::max),
+            arrows,
+            options,
+        }
+    }
+
+    pub fn with_options_screen<U: AsF64, V: AsF64, F: Fn(f64, f64) -> (U, V)>(
+        f: F,
+        domain: Domain,
+        options: VectorFieldOptions,
+    ) -> VectorField {
+        let w_screen = console_engine::crossterm::terminal::size().unwrap().0;
+        VectorField::with_options(f, domain, w_screen as u32, None, options)
+    }
+
+    pub(crate) fn render(&self) -> Screen {
+        let mut scr = Screen::new(self.plane.widths.total, self.plane.height);
+
+        self.plane.draw_axis(&mut scr, &self.options.theme);
+        if self.options.height_legend {
+            self.plane.draw_legends(&mut scr, &self.options.theme);
+        }
+        self.draw_arrows(&mut scr);
+
+        scr
+    }
+
+    pub fn draw(&self) {
+        let mut scr = self.render();
+        self.options.theme.paint_background(&mut scr);
+
+        draw_screen(scr);
+    }
+
+    fn color(&self, magnitude: f64) -> ColorWrapper {
+        let colors = &self.options.colors;
+        match colors.len() {
+            0 => ColorWrapper::default(),
+            1 => colors[0],
+            n => {
+                let t = if self.max_magnitude > 0f64 {
+                    magnitude / self.max_magnitude
+                } else {
+                    0f64
+                };
+                colors[(t * (n - 1) as f64).round() as usize]
+            }
+        }
+    }
+
+    fn draw_arrows(&self, scr: &mut Screen) {
+        for ((x, y), angle, magnitude) in self.arrows.iter() {
+            let arrow = if *magnitude == 0f64 {
+                NULL
+            } else {
+                let eighth = (angle / (PI / 4f64)).round().rem_euclid(8f64) as usize;
+                ARROWS[eighth]
+            };
+            scr.set_pxl(*x, *y, pixel::pxl_fg(arrow, self.color(*magnitude).into()));
+        }
+    }
+}
+
+impl fmt::Display for VectorField {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.draw();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console_engine::Color;
+
+    const SQUARE: Domain = Domain {
+        x: (-1f64, 1f64),
+        y: (-1f64, 1f64),
+    };
+
+    /// Row, arrow and color of each arrow of the field.
+    fn arrows(field: &VectorField) -> Vec<(i32, char, Color)> {
+        let scr = field.render();
+        field
+            .arrows
+            .iter()
+            .map(|((x, y), _, _)| {
+                let pxl = scr.get_pxl(*x, *y).unwrap();
+                (*y, pxl.chr, pxl.fg)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rotation_turns_counterclockwise() {
+        let field = VectorField::new(|x, y| (-y, x), SQUARE, 41, None);
+        let arrows = arrows(&field);
+        let (top, bottom) = (arrows[0].0, arrows[arrows.len() - 1].0);
+        let row = |r: i32| -> String {
+            arrows
+                .iter()
+                .filter(|(y, _, _)| *y == r)
+                .map(|(_, c, _)| *c)
+                .collect()
+        };
+        assert!(row(top).contains('←') && !row(top).contains('→'));
+        assert!(row(bottom).contains('→') && !row(bottom).contains('←'));
+    }
+
+    #[test]
+    fn arrows_keep_their_angle_on_screen() {
+        // Rows are taller than columns are wide, but the same scale on both axes keeps the diagonal
+        let field = VectorField::new(|_, _| (1f64, 1f64), SQUARE, 41, None);
+        assert!(arrows(&field).iter().all(|(_, c, _)| *c == '↗'));
+    }
+
+    #[test]
+    fn zero_vectors_are_dots() {
+        let options = VectorFieldOptions::builder()
+            .colors(vec![Color::Red.into(), Color::Blue.into()])
+            .build();
+        let field = VectorField::with_options(|_, _| (0f64, 0f64), SQUARE, 41, None, options);
+        let arrows = arrows(&field);
+        assert!(!arrows.is_empty());
+        assert!(arrows
+            .iter()
+            .all(|(_, c, color)| *c == NULL && *color == Color::Red));
+    }
+
+    #[test]
+    fn non_finite_vectors_are_left_out() {
+        let all = VectorField::new(|_, _| (1f64, 0f64), SQUARE, 41, None);
+        let field = VectorField::new(
+            |x, _| (if x < 0f64 { f64::NAN } else { 1f64 }, 0f64),
+            SQUARE,
+            41,
+            None,
+        );
+        assert!(field.arrows.len() < all.arrows.len());
+        assert!(field.arrows.iter().all(|(_, _, m)| m.is_finite()));
+        assert!(arrows(&field).iter().all(|(_, c, _)| *c == '→'));
+    }
+
+    #[test]
+    fn colors_follow_the_magnitude() {
+        let options = VectorFieldOptions::builder()
+            .colors(vec![
+                Color::Red.into(),
+                Color::Green.into(),
+                Color::Blue.into(),
+            ])
+            .build();
+        let field = VectorField::with_options(
+            |x, _| (x, 0f64),
+            Domain {
+                x: (0f64, 1f64),
+                y: (0f64, 1f64),
+            },
+            41,
+            None,
+            options,
+        );
+        let colors: Vec<Color> = arrows(&field)
+            .iter()
+            .filter(|(y, _, _)| *y == field.arrows[0].0 .1)
+            .map(|(_, _, color)| *color)
+            .collect();
+        assert_eq!(colors.first(), Some(&Color::Red));
+        assert!(colors.contains(&Color::Green));
+        assert_eq!(colors.last(), Some(&Color::Blue));
+    }
+}